@@ -0,0 +1,25 @@
+-- 기업실적분석 테이블의 나머지 행들을 저장하는 컬럼 추가
+
+ALTER TABLE fi_annuals
+	ADD COLUMN operating_margin FLOAT AFTER net_income, -- 영업이익률
+	ADD COLUMN net_margin FLOAT AFTER operating_margin, -- 순이익률
+	ADD COLUMN roe FLOAT AFTER net_margin, -- ROE(지배주주)
+	ADD COLUMN debt_ratio FLOAT AFTER roe, -- 부채비율
+	ADD COLUMN quick_ratio FLOAT AFTER debt_ratio, -- 당좌비율
+	ADD COLUMN reserve_ratio FLOAT AFTER quick_ratio, -- 유보율
+	ADD COLUMN eps FLOAT AFTER reserve_ratio, -- EPS(원)
+	ADD COLUMN per FLOAT AFTER eps, -- PER(배)
+	ADD COLUMN bps FLOAT AFTER per, -- BPS(원)
+	ADD COLUMN pbr FLOAT AFTER bps; -- PBR(배)
+
+ALTER TABLE fi_quarters
+	ADD COLUMN operating_margin FLOAT AFTER net_income, -- 영업이익률
+	ADD COLUMN net_margin FLOAT AFTER operating_margin, -- 순이익률
+	ADD COLUMN roe FLOAT AFTER net_margin, -- ROE(지배주주)
+	ADD COLUMN debt_ratio FLOAT AFTER roe, -- 부채비율
+	ADD COLUMN quick_ratio FLOAT AFTER debt_ratio, -- 당좌비율
+	ADD COLUMN reserve_ratio FLOAT AFTER quick_ratio, -- 유보율
+	ADD COLUMN eps FLOAT AFTER reserve_ratio, -- EPS(원)
+	ADD COLUMN per FLOAT AFTER eps, -- PER(배)
+	ADD COLUMN bps FLOAT AFTER per, -- BPS(원)
+	ADD COLUMN pbr FLOAT AFTER bps; -- PBR(배)
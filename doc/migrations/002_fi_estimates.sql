@@ -0,0 +1,26 @@
+-- 컨센서스 추정실적(E) 스냅샷 테이블 추가
+
+CREATE TABLE fi_estimates (
+	stock_code VARCHAR(9) NOT NULL, -- 종목코드
+	term enum('ANNUAL', 'QUARTER') NOT NULL, -- 연간/분기 구분
+	year SMALLINT UNSIGNED NOT NULL, -- 기준년도
+	month TINYINT UNSIGNED NOT NULL, -- 기준월
+	capture_date DATE NOT NULL, -- 수집일자
+	sales FLOAT, -- 매출액
+	profit FLOAT, -- 영업이익
+	net_income FLOAT, -- 당기순이익
+	operating_margin FLOAT, -- 영업이익률
+	net_margin FLOAT, -- 순이익률
+	roe FLOAT, -- ROE(지배주주)
+	debt_ratio FLOAT, -- 부채비율
+	quick_ratio FLOAT, -- 당좌비율
+	reserve_ratio FLOAT, -- 유보율
+	eps FLOAT, -- EPS(원)
+	per FLOAT, -- PER(배)
+	bps FLOAT, -- BPS(원)
+	pbr FLOAT, -- PBR(배)
+	dividend FLOAT, -- 주당배당금
+	dividend_yield FLOAT, -- 배당수익률
+	PRIMARY KEY (stock_code, term, year, month, capture_date),
+	CHECK (month BETWEEN 1 AND 12)
+);
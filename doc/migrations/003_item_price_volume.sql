@@ -0,0 +1,5 @@
+-- 일별 시세에 거래량, 거래대금 컬럼 추가
+
+ALTER TABLE item_price
+	ADD COLUMN volume BIGINT UNSIGNED AFTER diff, -- 거래량
+	ADD COLUMN trading_value BIGINT UNSIGNED AFTER volume; -- 거래대금
@@ -0,0 +1,10 @@
+-- 종목 상장/상장폐지 변동 기록 테이블 추가
+
+CREATE TABLE item_listing_changes (
+	code VARCHAR(9) NOT NULL, -- 종목코드
+	change_date DATE NOT NULL, -- 변동 확인일자 (종목마스터 기준일자)
+	kind enum('LISTED', 'DELISTED') NOT NULL, -- 신규상장/상장폐지
+	name VARCHAR(50) NOT NULL, -- 종목명
+	market enum('KOSPI', 'KOSDAQ', 'ETF') NOT NULL, -- 시장구분
+	PRIMARY KEY (code, change_date, kind)
+);
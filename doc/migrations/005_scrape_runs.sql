@@ -0,0 +1,19 @@
+-- 수집 실행 기록 테이블 추가 (`stock-fn-scrap --resume`)
+
+CREATE TABLE scrape_runs (
+	id BIGINT UNSIGNED NOT NULL AUTO_INCREMENT, -- 실행 ID
+	source VARCHAR(16) NOT NULL, -- 데이터 소스
+	started_at DATETIME NOT NULL, -- 시작시각
+	finished_at DATETIME, -- 완료시각 (오류 없이 모든 종목을 처리한 경우)
+	PRIMARY KEY (id),
+	INDEX (source, finished_at)
+);
+
+CREATE TABLE scrape_run_items (
+	run_id BIGINT UNSIGNED NOT NULL, -- 실행 ID
+	stock_code VARCHAR(9) NOT NULL, -- 종목코드
+	status enum('FETCHED', 'SKIPPED', 'ERRORED') NOT NULL, -- 처리결과
+	message TEXT, -- 오류 메시지
+	updated_at DATETIME NOT NULL, -- 처리시각
+	PRIMARY KEY (run_id, stock_code)
+);
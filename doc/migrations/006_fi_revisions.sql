@@ -0,0 +1,16 @@
+-- 실적 항목 수정 내역 테이블 추가
+
+CREATE TABLE fi_revisions (
+	id BIGINT UNSIGNED NOT NULL AUTO_INCREMENT,
+	stock_code VARCHAR(9) NOT NULL, -- 종목코드
+	term enum('ANNUAL', 'QUARTER') NOT NULL, -- 연간/분기 구분
+	year SMALLINT UNSIGNED NOT NULL, -- 기준년도
+	month TINYINT UNSIGNED NOT NULL, -- 기준월 (수정 전)
+	field VARCHAR(32) NOT NULL, -- 컬럼 이름
+	old_value FLOAT, -- 이전 값
+	new_value FLOAT, -- 새 값
+	source VARCHAR(16) NOT NULL, -- 데이터 소스
+	revised_at DATETIME NOT NULL, -- 새 값의 수집시각
+	PRIMARY KEY (id),
+	INDEX (stock_code, term, revised_at)
+);
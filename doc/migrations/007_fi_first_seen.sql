@@ -0,0 +1,12 @@
+-- fi_annuals, fi_quarters 에 first_seen 컬럼 추가
+--
+-- 기존 행은 언제 처음 수집했는지 알 수 없으므로 공시기한으로 추정한다.
+-- (사업보고서: 결산월 말일 + 90일, 분기/반기보고서: 분기말 + 45일)
+
+ALTER TABLE fi_annuals ADD COLUMN first_seen DATE AFTER dividend_yield;
+UPDATE fi_annuals SET first_seen = DATE_ADD(LAST_DAY(CONCAT(year, '-', month, '-01')), INTERVAL 90 DAY) WHERE first_seen IS NULL;
+ALTER TABLE fi_annuals MODIFY COLUMN first_seen DATE NOT NULL;
+
+ALTER TABLE fi_quarters ADD COLUMN first_seen DATE AFTER dividend_yield;
+UPDATE fi_quarters SET first_seen = DATE_ADD(LAST_DAY(CONCAT(year, '-', month, '-01')), INTERVAL 45 DAY) WHERE first_seen IS NULL;
+ALTER TABLE fi_quarters MODIFY COLUMN first_seen DATE NOT NULL;
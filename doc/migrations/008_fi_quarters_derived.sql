@@ -0,0 +1,3 @@
+-- fi_quarters 에 derived 컬럼 추가 (연간 실적에서 계산한 분기 실적 표시)
+
+ALTER TABLE fi_quarters ADD COLUMN derived BOOLEAN NOT NULL DEFAULT FALSE AFTER dividend_yield;
@@ -0,0 +1,13 @@
+-- 결산월을 바꾼 해에는 같은 년도에 연간 실적이 둘 생길 수 있으므로 기준월까지 키에 포함
+-- 종목별 결산월 테이블 추가
+
+ALTER TABLE fi_annuals DROP PRIMARY KEY, ADD PRIMARY KEY (stock_code, year, month);
+
+CREATE TABLE fi_fiscal_months (
+	stock_code VARCHAR(9) NOT NULL, -- 종목코드
+	fiscal_month TINYINT UNSIGNED NOT NULL, -- 결산월
+	since_year SMALLINT UNSIGNED NOT NULL, -- 이 결산월로 끝난 첫 회계연도
+	since_month TINYINT UNSIGNED NOT NULL, -- 이 결산월로 끝난 첫 회계연도의 기준월
+	PRIMARY KEY (stock_code),
+	CHECK (fiscal_month BETWEEN 1 AND 12)
+);
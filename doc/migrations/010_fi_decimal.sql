@@ -0,0 +1,57 @@
+-- 실적 수치를 FLOAT 에서 DECIMAL 로 변경 (억원 단위 큰 금액의 정밀도, 비율 값의 오차)
+-- 기존 값은 소수점 둘째 자리로 반올림된다
+
+ALTER TABLE fi_annuals
+	MODIFY sales DECIMAL(20,2),
+	MODIFY profit DECIMAL(20,2),
+	MODIFY net_income DECIMAL(20,2),
+	MODIFY operating_margin DECIMAL(20,2),
+	MODIFY net_margin DECIMAL(20,2),
+	MODIFY roe DECIMAL(20,2),
+	MODIFY debt_ratio DECIMAL(20,2),
+	MODIFY quick_ratio DECIMAL(20,2),
+	MODIFY reserve_ratio DECIMAL(20,2),
+	MODIFY eps DECIMAL(20,2),
+	MODIFY per DECIMAL(20,2),
+	MODIFY bps DECIMAL(20,2),
+	MODIFY pbr DECIMAL(20,2),
+	MODIFY dividend DECIMAL(20,2),
+	MODIFY dividend_yield DECIMAL(20,2);
+
+ALTER TABLE fi_quarters
+	MODIFY sales DECIMAL(20,2),
+	MODIFY profit DECIMAL(20,2),
+	MODIFY net_income DECIMAL(20,2),
+	MODIFY operating_margin DECIMAL(20,2),
+	MODIFY net_margin DECIMAL(20,2),
+	MODIFY roe DECIMAL(20,2),
+	MODIFY debt_ratio DECIMAL(20,2),
+	MODIFY quick_ratio DECIMAL(20,2),
+	MODIFY reserve_ratio DECIMAL(20,2),
+	MODIFY eps DECIMAL(20,2),
+	MODIFY per DECIMAL(20,2),
+	MODIFY bps DECIMAL(20,2),
+	MODIFY pbr DECIMAL(20,2),
+	MODIFY dividend DECIMAL(20,2),
+	MODIFY dividend_yield DECIMAL(20,2);
+
+ALTER TABLE fi_estimates
+	MODIFY sales DECIMAL(20,2),
+	MODIFY profit DECIMAL(20,2),
+	MODIFY net_income DECIMAL(20,2),
+	MODIFY operating_margin DECIMAL(20,2),
+	MODIFY net_margin DECIMAL(20,2),
+	MODIFY roe DECIMAL(20,2),
+	MODIFY debt_ratio DECIMAL(20,2),
+	MODIFY quick_ratio DECIMAL(20,2),
+	MODIFY reserve_ratio DECIMAL(20,2),
+	MODIFY eps DECIMAL(20,2),
+	MODIFY per DECIMAL(20,2),
+	MODIFY bps DECIMAL(20,2),
+	MODIFY pbr DECIMAL(20,2),
+	MODIFY dividend DECIMAL(20,2),
+	MODIFY dividend_yield DECIMAL(20,2);
+
+ALTER TABLE fi_revisions
+	MODIFY old_value DECIMAL(20,2),
+	MODIFY new_value DECIMAL(20,2);
@@ -0,0 +1,15 @@
+-- 연결/별도 재무제표 구분을 실적 키에 포함
+-- 기존 데이터는 연결로 본다
+
+ALTER TABLE fi_annuals
+	ADD COLUMN basis enum('CONSOLIDATED', 'SEPARATE') NOT NULL DEFAULT 'CONSOLIDATED' AFTER stock_code,
+	DROP PRIMARY KEY,
+	ADD PRIMARY KEY (stock_code, basis, year, month);
+
+ALTER TABLE fi_quarters
+	ADD COLUMN basis enum('CONSOLIDATED', 'SEPARATE') NOT NULL DEFAULT 'CONSOLIDATED' AFTER stock_code,
+	DROP PRIMARY KEY,
+	ADD PRIMARY KEY (stock_code, basis, year, month);
+
+ALTER TABLE fi_revisions
+	ADD COLUMN basis enum('CONSOLIDATED', 'SEPARATE') NOT NULL DEFAULT 'CONSOLIDATED' AFTER term;
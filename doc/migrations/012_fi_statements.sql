@@ -0,0 +1,35 @@
+-- 재무상태표, 현금흐름표 주요 항목 테이블 추가
+
+CREATE TABLE fi_balance_sheets (
+	stock_code VARCHAR(9) NOT NULL, -- 종목코드
+	term enum('ANNUAL', 'QUARTER') NOT NULL, -- 연간/분기 구분
+	basis enum('CONSOLIDATED', 'SEPARATE') NOT NULL DEFAULT 'CONSOLIDATED', -- 연결/별도 구분
+	year SMALLINT UNSIGNED NOT NULL, -- 기준년도
+	month TINYINT UNSIGNED NOT NULL, -- 기준월
+	total_assets DECIMAL(20,2), -- 자산총계
+	total_liabilities DECIMAL(20,2), -- 부채총계
+	total_equity DECIMAL(20,2), -- 자본총계
+	owners_equity DECIMAL(20,2), -- 지배기업 소유주지분
+	cash DECIMAL(20,2), -- 현금및현금성자산
+	borrowings DECIMAL(20,2), -- 차입금
+	first_seen DATE NOT NULL, -- 처음 수집한 날짜
+	PRIMARY KEY (stock_code, term, basis, year, month),
+	CHECK (month BETWEEN 1 AND 12)
+);
+
+CREATE TABLE fi_cash_flows (
+	stock_code VARCHAR(9) NOT NULL, -- 종목코드
+	term enum('ANNUAL', 'QUARTER') NOT NULL, -- 연간/분기 구분
+	basis enum('CONSOLIDATED', 'SEPARATE') NOT NULL DEFAULT 'CONSOLIDATED', -- 연결/별도 구분
+	year SMALLINT UNSIGNED NOT NULL, -- 기준년도
+	month TINYINT UNSIGNED NOT NULL, -- 기준월
+	operating DECIMAL(20,2), -- 영업활동현금흐름
+	investing DECIMAL(20,2), -- 투자활동현금흐름
+	financing DECIMAL(20,2), -- 재무활동현금흐름
+	capex DECIMAL(20,2), -- 유형자산의 취득
+	fcf DECIMAL(20,2), -- 잉여현금흐름
+	cumulative BOOLEAN NOT NULL DEFAULT FALSE, -- 회계연도 초부터의 누적 금액인지 (분기보고서)
+	first_seen DATE NOT NULL, -- 처음 수집한 날짜
+	PRIMARY KEY (stock_code, term, basis, year, month),
+	CHECK (month BETWEEN 1 AND 12)
+);
@@ -0,0 +1,4 @@
+-- 백필 값이 저장된 값과 달라 반영하지 않은 기록을 구분한다
+
+ALTER TABLE fi_revisions
+	ADD applied BOOLEAN NOT NULL DEFAULT TRUE AFTER new_value;
@@ -21,30 +21,189 @@ CREATE TABLE item_info (
 	etf_tax_type VARCHAR(30) -- 과세유형
 );
 
+DROP TABLE IF EXISTS item_listing_changes;
+CREATE TABLE item_listing_changes (
+	code VARCHAR(9) NOT NULL, -- 종목코드
+	change_date DATE NOT NULL, -- 변동 확인일자 (종목마스터 기준일자)
+	kind enum('LISTED', 'DELISTED') NOT NULL, -- 신규상장/상장폐지
+	name VARCHAR(50) NOT NULL, -- 종목명
+	market enum('KOSPI', 'KOSDAQ', 'ETF') NOT NULL, -- 시장구분
+	PRIMARY KEY (code, change_date, kind)
+);
+
+DROP TABLE IF EXISTS item_price;
+CREATE TABLE item_price (
+	code VARCHAR(9) NOT NULL, -- 종목코드
+	ord_date DATE NOT NULL, -- 일자
+	opening INT UNSIGNED, -- 시가
+	highest INT UNSIGNED, -- 고가
+	lowest INT UNSIGNED, -- 저가
+	closing INT UNSIGNED, -- 종가
+	diff INT, -- 전일대비
+	volume BIGINT UNSIGNED, -- 거래량
+	trading_value BIGINT UNSIGNED, -- 거래대금
+	PRIMARY KEY (code, ord_date)
+);
+
 DROP TABLE IF EXISTS fi_annuals;
 CREATE TABLE fi_annuals (
 	stock_code VARCHAR(9) NOT NULL, -- 종목코드
+	basis enum('CONSOLIDATED', 'SEPARATE') NOT NULL DEFAULT 'CONSOLIDATED', -- 연결/별도 구분
 	year SMALLINT UNSIGNED NOT NULL, -- 기준년도
 	month TINYINT UNSIGNED NOT NULL, -- 기준월
-	sales FLOAT, -- 매출액
-	profit FLOAT, -- 영업이익
-	net_income FLOAT, -- 당기순이익
-	dividend FLOAT, -- 주당배당금
-	dividend_yield FLOAT, -- 배당수익률
-	PRIMARY KEY (stock_code, year),
+	sales DECIMAL(20,2), -- 매출액
+	profit DECIMAL(20,2), -- 영업이익
+	net_income DECIMAL(20,2), -- 당기순이익
+	operating_margin DECIMAL(20,2), -- 영업이익률
+	net_margin DECIMAL(20,2), -- 순이익률
+	roe DECIMAL(20,2), -- ROE(지배주주)
+	debt_ratio DECIMAL(20,2), -- 부채비율
+	quick_ratio DECIMAL(20,2), -- 당좌비율
+	reserve_ratio DECIMAL(20,2), -- 유보율
+	eps DECIMAL(20,2), -- EPS(원)
+	per DECIMAL(20,2), -- PER(배)
+	bps DECIMAL(20,2), -- BPS(원)
+	pbr DECIMAL(20,2), -- PBR(배)
+	dividend DECIMAL(20,2), -- 주당배당금
+	dividend_yield DECIMAL(20,2), -- 배당수익률
+	first_seen DATE NOT NULL, -- 해당 기간의 실적을 처음 수집한 날짜
+	PRIMARY KEY (stock_code, basis, year, month),
 	CHECK (month BETWEEN 1 AND 12)
 );
 
+DROP TABLE IF EXISTS fi_fiscal_months;
+CREATE TABLE fi_fiscal_months (
+	stock_code VARCHAR(9) NOT NULL, -- 종목코드
+	fiscal_month TINYINT UNSIGNED NOT NULL, -- 결산월
+	since_year SMALLINT UNSIGNED NOT NULL, -- 이 결산월로 끝난 첫 회계연도
+	since_month TINYINT UNSIGNED NOT NULL, -- 이 결산월로 끝난 첫 회계연도의 기준월
+	PRIMARY KEY (stock_code),
+	CHECK (fiscal_month BETWEEN 1 AND 12)
+);
+
 DROP TABLE IF EXISTS fi_quarters;
 CREATE TABLE fi_quarters (
 	stock_code VARCHAR(9) NOT NULL, -- 종목코드
+	basis enum('CONSOLIDATED', 'SEPARATE') NOT NULL DEFAULT 'CONSOLIDATED', -- 연결/별도 구분
+	year SMALLINT UNSIGNED NOT NULL, -- 기준년도
+	month TINYINT UNSIGNED NOT NULL, -- 기준월
+	sales DECIMAL(20,2), -- 매출액
+	profit DECIMAL(20,2), -- 영업이익
+	net_income DECIMAL(20,2), -- 당기순이익
+	operating_margin DECIMAL(20,2), -- 영업이익률
+	net_margin DECIMAL(20,2), -- 순이익률
+	roe DECIMAL(20,2), -- ROE(지배주주)
+	debt_ratio DECIMAL(20,2), -- 부채비율
+	quick_ratio DECIMAL(20,2), -- 당좌비율
+	reserve_ratio DECIMAL(20,2), -- 유보율
+	eps DECIMAL(20,2), -- EPS(원)
+	per DECIMAL(20,2), -- PER(배)
+	bps DECIMAL(20,2), -- BPS(원)
+	pbr DECIMAL(20,2), -- PBR(배)
+	dividend DECIMAL(20,2), -- 주당배당금
+	dividend_yield DECIMAL(20,2), -- 배당수익률
+	derived BOOLEAN NOT NULL DEFAULT FALSE, -- 연간 실적에서 계산한 분기 실적 여부
+	first_seen DATE NOT NULL, -- 해당 기간의 실적을 처음 수집한 날짜
+	PRIMARY KEY (stock_code, basis, year, month),
+	CHECK (month BETWEEN 1 AND 12)
+);
+
+DROP TABLE IF EXISTS fi_balance_sheets;
+CREATE TABLE fi_balance_sheets (
+	stock_code VARCHAR(9) NOT NULL, -- 종목코드
+	term enum('ANNUAL', 'QUARTER') NOT NULL, -- 연간/분기 구분
+	basis enum('CONSOLIDATED', 'SEPARATE') NOT NULL DEFAULT 'CONSOLIDATED', -- 연결/별도 구분
 	year SMALLINT UNSIGNED NOT NULL, -- 기준년도
 	month TINYINT UNSIGNED NOT NULL, -- 기준월
-	sales FLOAT, -- 매출액
-	profit FLOAT, -- 영업이익
-	net_income FLOAT, -- 당기순이익
-	dividend FLOAT, -- 주당배당금
-	dividend_yield FLOAT, -- 배당수익률
-	PRIMARY KEY (stock_code, year, month),
+	total_assets DECIMAL(20,2), -- 자산총계
+	total_liabilities DECIMAL(20,2), -- 부채총계
+	total_equity DECIMAL(20,2), -- 자본총계
+	owners_equity DECIMAL(20,2), -- 지배기업 소유주지분
+	cash DECIMAL(20,2), -- 현금및현금성자산
+	borrowings DECIMAL(20,2), -- 차입금
+	first_seen DATE NOT NULL, -- 처음 수집한 날짜
+	PRIMARY KEY (stock_code, term, basis, year, month),
 	CHECK (month BETWEEN 1 AND 12)
 );
+
+DROP TABLE IF EXISTS fi_cash_flows;
+CREATE TABLE fi_cash_flows (
+	stock_code VARCHAR(9) NOT NULL, -- 종목코드
+	term enum('ANNUAL', 'QUARTER') NOT NULL, -- 연간/분기 구분
+	basis enum('CONSOLIDATED', 'SEPARATE') NOT NULL DEFAULT 'CONSOLIDATED', -- 연결/별도 구분
+	year SMALLINT UNSIGNED NOT NULL, -- 기준년도
+	month TINYINT UNSIGNED NOT NULL, -- 기준월
+	operating DECIMAL(20,2), -- 영업활동현금흐름
+	investing DECIMAL(20,2), -- 투자활동현금흐름
+	financing DECIMAL(20,2), -- 재무활동현금흐름
+	capex DECIMAL(20,2), -- 유형자산의 취득
+	fcf DECIMAL(20,2), -- 잉여현금흐름
+	cumulative BOOLEAN NOT NULL DEFAULT FALSE, -- 회계연도 초부터의 누적 금액인지 (분기보고서)
+	first_seen DATE NOT NULL, -- 처음 수집한 날짜
+	PRIMARY KEY (stock_code, term, basis, year, month),
+	CHECK (month BETWEEN 1 AND 12)
+);
+
+DROP TABLE IF EXISTS fi_estimates;
+CREATE TABLE fi_estimates (
+	stock_code VARCHAR(9) NOT NULL, -- 종목코드
+	term enum('ANNUAL', 'QUARTER') NOT NULL, -- 연간/분기 구분
+	year SMALLINT UNSIGNED NOT NULL, -- 기준년도
+	month TINYINT UNSIGNED NOT NULL, -- 기준월
+	capture_date DATE NOT NULL, -- 수집일자
+	sales DECIMAL(20,2), -- 매출액
+	profit DECIMAL(20,2), -- 영업이익
+	net_income DECIMAL(20,2), -- 당기순이익
+	operating_margin DECIMAL(20,2), -- 영업이익률
+	net_margin DECIMAL(20,2), -- 순이익률
+	roe DECIMAL(20,2), -- ROE(지배주주)
+	debt_ratio DECIMAL(20,2), -- 부채비율
+	quick_ratio DECIMAL(20,2), -- 당좌비율
+	reserve_ratio DECIMAL(20,2), -- 유보율
+	eps DECIMAL(20,2), -- EPS(원)
+	per DECIMAL(20,2), -- PER(배)
+	bps DECIMAL(20,2), -- BPS(원)
+	pbr DECIMAL(20,2), -- PBR(배)
+	dividend DECIMAL(20,2), -- 주당배당금
+	dividend_yield DECIMAL(20,2), -- 배당수익률
+	PRIMARY KEY (stock_code, term, year, month, capture_date),
+	CHECK (month BETWEEN 1 AND 12)
+);
+
+DROP TABLE IF EXISTS scrape_runs;
+CREATE TABLE scrape_runs (
+	id BIGINT UNSIGNED NOT NULL AUTO_INCREMENT, -- 실행 ID
+	source VARCHAR(16) NOT NULL, -- 데이터 소스
+	started_at DATETIME NOT NULL, -- 시작시각
+	finished_at DATETIME, -- 완료시각 (오류 없이 모든 종목을 처리한 경우)
+	PRIMARY KEY (id),
+	INDEX (source, finished_at)
+);
+
+DROP TABLE IF EXISTS scrape_run_items;
+CREATE TABLE scrape_run_items (
+	run_id BIGINT UNSIGNED NOT NULL, -- 실행 ID
+	stock_code VARCHAR(9) NOT NULL, -- 종목코드
+	status enum('FETCHED', 'SKIPPED', 'ERRORED') NOT NULL, -- 처리결과
+	message TEXT, -- 오류 메시지
+	updated_at DATETIME NOT NULL, -- 처리시각
+	PRIMARY KEY (run_id, stock_code)
+);
+
+DROP TABLE IF EXISTS fi_revisions;
+CREATE TABLE fi_revisions (
+	id BIGINT UNSIGNED NOT NULL AUTO_INCREMENT,
+	stock_code VARCHAR(9) NOT NULL, -- 종목코드
+	term enum('ANNUAL', 'QUARTER') NOT NULL, -- 연간/분기 구분
+	basis enum('CONSOLIDATED', 'SEPARATE') NOT NULL DEFAULT 'CONSOLIDATED', -- 연결/별도 구분
+	year SMALLINT UNSIGNED NOT NULL, -- 기준년도
+	month TINYINT UNSIGNED NOT NULL, -- 기준월 (수정 전)
+	field VARCHAR(32) NOT NULL, -- 컬럼 이름
+	old_value DECIMAL(20,2), -- 이전 값
+	new_value DECIMAL(20,2), -- 새 값
+	applied BOOLEAN NOT NULL DEFAULT TRUE, -- 새 값을 저장했는지 (반영하지 않은 백필 값이면 FALSE)
+	source VARCHAR(16) NOT NULL, -- 데이터 소스
+	revised_at DATETIME NOT NULL, -- 새 값의 수집시각
+	PRIMARY KEY (id),
+	INDEX (stock_code, term, revised_at)
+);
@@ -0,0 +1,15 @@
+use std::collections::BTreeMap;
+
+use crate::{entities::FinancialInfo, types::{StatementBasis, YearMonth}};
+
+/// 기간마다 `preferred` 구분의 실적을 고르고, 없으면 다른 구분의 실적을 쓴다. 결과는 년월 순
+pub fn select_basis(list: Vec<FinancialInfo>, preferred: StatementBasis) -> Vec<FinancialInfo> {
+	let mut selected = BTreeMap::<YearMonth, FinancialInfo>::new();
+	for fi in list {
+		let keep = selected.get(&fi.year_month).is_some_and(|prev| prev.basis == preferred);
+		if !keep {
+			selected.insert(fi.year_month, fi);
+		}
+	}
+	selected.into_values().collect()
+}
@@ -0,0 +1,310 @@
+use rust_decimal::prelude::ToPrimitive;
+use serde::{Serialize, Serializer};
+
+use crate::{entities::{FiEstimate, FinancialInfo}, types::{Amount, FiTerm, YearMonth}};
+
+/// 이전 기간 대비 증감
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub enum Growth {
+	/// 증가율(%)
+	Rate(f64),
+	/// 흑자전환
+	TurnedProfit,
+	/// 적자전환
+	TurnedLoss,
+	/// 적자지속
+	ContinuedLoss,
+	/// 값이 없거나 이전 기간이 0
+	Unavailable,
+}
+
+impl Growth {
+	/// 이전 기간이 적자이면 증가율 대신 흑자전환/적자지속으로 구분한다
+	pub fn of(base: Option<Amount>, current: Option<Amount>) -> Self {
+		let (Some(base), Some(current)) = (base.and_then(|v| v.to_f64()), current.and_then(|v| v.to_f64())) else {
+			return Growth::Unavailable;
+		};
+		if base > 0.0 {
+			if current < 0.0 {
+				Growth::TurnedLoss
+			} else {
+				Growth::Rate((current - base) / base * 100.0)
+			}
+		} else if base < 0.0 {
+			if current > 0.0 {
+				Growth::TurnedProfit
+			} else {
+				Growth::ContinuedLoss
+			}
+		} else if current > 0.0 {
+			Growth::TurnedProfit
+		} else if current < 0.0 {
+			Growth::TurnedLoss
+		} else {
+			Growth::Unavailable
+		}
+	}
+
+	/// 매출액처럼 적자가 없는 항목. 이전 기간이 0 이하이면 증가율을 계산하지 않는다
+	pub fn of_revenue(base: Option<Amount>, current: Option<Amount>) -> Self {
+		match Growth::of(base, current) {
+			rate @ Growth::Rate(_) => rate,
+			_ => Growth::Unavailable,
+		}
+	}
+
+	pub fn as_label(&self) -> Option<&'static str> {
+		match self {
+			Growth::TurnedProfit => Some("흑자전환"),
+			Growth::TurnedLoss => Some("적자전환"),
+			Growth::ContinuedLoss => Some("적자지속"),
+			Growth::Rate(_) | Growth::Unavailable => None,
+		}
+	}
+}
+
+/// CSV 에는 증가율은 소수점 첫째 자리까지, 흑자전환 등은 한글로, 없는 값은 빈 칸으로 쓴다
+impl Serialize for Growth {
+	fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+		match self {
+			Growth::Rate(v) => serializer.serialize_f64((v * 10.0).round() / 10.0),
+			Growth::Unavailable => serializer.serialize_none(),
+			_ => serializer.serialize_str(self.as_label().unwrap()),
+		}
+	}
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct GrowthRates {
+	/// 비교 기간
+	pub year_month: YearMonth,
+	/// 기준 기간
+	pub base_year_month: YearMonth,
+	pub sales: Growth,
+	pub profit: Growth,
+	pub net_income: Growth,
+}
+
+impl GrowthRates {
+	pub fn compare(base: &FinancialInfo, current: &FinancialInfo) -> Self {
+		Self {
+			year_month: current.year_month,
+			base_year_month: base.year_month,
+			sales: Growth::of_revenue(base.sales, current.sales),
+			profit: Growth::of(base.profit, current.profit),
+			net_income: Growth::of(base.net_income, current.net_income),
+		}
+	}
+}
+
+/// 가장 최근 기간과 1년 전 같은 기간의 비교. 연간, 분기 실적 모두에 쓸 수 있다
+pub fn year_over_year(list: &[FinancialInfo]) -> Option<GrowthRates> {
+	growth_from(list, 12)
+}
+
+/// 가장 최근 분기와 직전 분기의 비교
+pub fn quarter_over_quarter(quarters: &[FinancialInfo]) -> Option<GrowthRates> {
+	growth_from(quarters, 3)
+}
+
+fn growth_from(list: &[FinancialInfo], months: i32) -> Option<GrowthRates> {
+	let current = list.iter().max_by_key(|fi| fi.year_month)?;
+	let base_ym = current.year_month.sub_months(months);
+	let base = list.iter().find(|fi| fi.year_month == base_ym)?;
+	Some(GrowthRates::compare(base, current))
+}
+
+/// 어닝 서프라이즈 판정 기준. 영업이익이 컨센서스와 이 비율(%) 이상 차이나면 서프라이즈/쇼크
+pub const DEFAULT_SURPRISE_THRESHOLD: f64 = 10.0;
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum SurpriseKind {
+	/// 어닝 서프라이즈
+	Positive,
+	/// 컨센서스 부합
+	InLine,
+	/// 어닝 쇼크
+	Negative,
+}
+
+impl SurpriseKind {
+	pub fn as_label(&self) -> &'static str {
+		match self {
+			SurpriseKind::Positive => "서프라이즈",
+			SurpriseKind::InLine => "부합",
+			SurpriseKind::Negative => "쇼크",
+		}
+	}
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct EarningsSurprise {
+	pub year_month: YearMonth,
+	/// 컨센서스 영업이익
+	pub estimate: Amount,
+	/// 발표된 영업이익
+	pub actual: Amount,
+	/// 컨센서스 대비 차이(%). 컨센서스가 0 이면 `None`
+	pub deviation: Option<f64>,
+	pub kind: SurpriseKind,
+}
+
+/// 영업이익을 컨센서스와 비교한다. 어느 한쪽이라도 값이 없으면 `None`
+pub fn classify_surprise(actual: &FinancialInfo, estimate: &FinancialInfo, threshold: f64) -> Option<EarningsSurprise> {
+	let (actual_profit, estimate_profit) = (actual.profit?, estimate.profit?);
+	let deviation = (!estimate_profit.is_zero())
+		.then(|| (actual_profit - estimate_profit) / estimate_profit.abs() * Amount::ONE_HUNDRED)
+		.and_then(|d| d.to_f64());
+	let kind = match deviation {
+		Some(d) if d >= threshold => SurpriseKind::Positive,
+		Some(d) if d <= -threshold => SurpriseKind::Negative,
+		Some(_) => SurpriseKind::InLine,
+		None if actual_profit > Amount::ZERO => SurpriseKind::Positive,
+		None if actual_profit < Amount::ZERO => SurpriseKind::Negative,
+		None => SurpriseKind::InLine,
+	};
+	Some(EarningsSurprise {
+		year_month: actual.year_month,
+		estimate: estimate_profit,
+		actual: actual_profit,
+		deviation,
+		kind,
+	})
+}
+
+/// 가장 최근 분기 실적을 그 분기에 대해 마지막으로 수집된 컨센서스와 비교한다.
+///
+/// 실적이 발표되면 해당 분기의 추정치는 더 이상 수집되지 않으므로, 마지막 스냅샷이 발표 직전의 컨센서스이다.
+pub fn latest_quarter_surprise(quarters: &[FinancialInfo], estimates: &[FiEstimate], threshold: f64) -> Option<EarningsSurprise> {
+	let latest = quarters.iter().max_by_key(|fi| fi.year_month)?;
+	let estimate = estimates.iter()
+		.filter(|e| e.term == FiTerm::Quarter && e.info.year_month == latest.year_month)
+		.max_by_key(|e| e.capture_date)?;
+	classify_surprise(latest, &estimate.info, threshold)
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	fn amount(v: i64) -> Option<Amount> {
+		Some(Amount::from(v))
+	}
+
+	#[test]
+	fn turnaround_labels() {
+		assert_eq!(Growth::of(amount(100), amount(120)), Growth::Rate(20.0));
+		assert_eq!(Growth::of(amount(-10), amount(5)), Growth::TurnedProfit);
+		assert_eq!(Growth::of(amount(0), amount(5)), Growth::TurnedProfit);
+		assert_eq!(Growth::of(amount(10), amount(-5)), Growth::TurnedLoss);
+		assert_eq!(Growth::of(amount(-10), amount(-5)), Growth::ContinuedLoss);
+		assert_eq!(Growth::of(None, amount(5)), Growth::Unavailable);
+	}
+
+	#[test]
+	fn sales_from_zero_is_not_a_turnaround() {
+		assert_eq!(Growth::of_revenue(amount(0), amount(5)), Growth::Unavailable);
+		assert_eq!(Growth::of_revenue(amount(100), amount(50)), Growth::Rate(-50.0));
+
+		let base = FinancialInfo { year_month: YearMonth::new(2023, 3), sales: amount(0), profit: amount(0), ..Default::default() };
+		let current = FinancialInfo { year_month: YearMonth::new(2024, 3), sales: amount(10), profit: amount(3), ..Default::default() };
+		let rates = GrowthRates::compare(&base, &current);
+		assert_eq!(rates.sales, Growth::Unavailable);
+		assert_eq!(rates.profit, Growth::TurnedProfit);
+	}
+
+	fn fi(year_month: &str, profit: Option<i64>) -> FinancialInfo {
+		FinancialInfo {
+			stock_code: "005930".to_string(),
+			year_month: year_month.parse().unwrap(),
+			sales: amount(1000),
+			profit: profit.map(Amount::from),
+			net_income: amount(50),
+			..Default::default()
+		}
+	}
+
+	fn estimate(term: FiTerm, year_month: &str, profit: i64, capture_date: &str) -> FiEstimate {
+		FiEstimate {
+			term,
+			capture_date: capture_date.parse().unwrap(),
+			info: fi(year_month, Some(profit)),
+		}
+	}
+
+	#[test]
+	fn surprise_around_threshold() {
+		let consensus = fi("2024.06", Some(100));
+		let kind = |actual: i64| classify_surprise(&fi("2024.06", Some(actual)), &consensus, DEFAULT_SURPRISE_THRESHOLD).unwrap().kind;
+		assert_eq!(kind(110), SurpriseKind::Positive);
+		assert_eq!(kind(109), SurpriseKind::InLine);
+		assert_eq!(kind(100), SurpriseKind::InLine);
+		assert_eq!(kind(91), SurpriseKind::InLine);
+		assert_eq!(kind(90), SurpriseKind::Negative);
+
+		let surprise = classify_surprise(&fi("2024.06", Some(125)), &consensus, DEFAULT_SURPRISE_THRESHOLD).unwrap();
+		assert_eq!(surprise.deviation, Some(25.0));
+		assert_eq!((surprise.estimate, surprise.actual), (Amount::from(100), Amount::from(125)));
+	}
+
+	#[test]
+	fn surprise_against_loss_or_zero_consensus() {
+		// 적자 컨센서스보다 적자가 줄면 서프라이즈
+		let surprise = classify_surprise(&fi("2024.06", Some(-50)), &fi("2024.06", Some(-100)), DEFAULT_SURPRISE_THRESHOLD).unwrap();
+		assert_eq!(surprise.deviation, Some(50.0));
+		assert_eq!(surprise.kind, SurpriseKind::Positive);
+
+		let zero = fi("2024.06", Some(0));
+		let surprise = classify_surprise(&fi("2024.06", Some(-1)), &zero, DEFAULT_SURPRISE_THRESHOLD).unwrap();
+		assert_eq!(surprise.deviation, None);
+		assert_eq!(surprise.kind, SurpriseKind::Negative);
+	}
+
+	#[test]
+	fn surprise_needs_both_figures() {
+		assert_eq!(classify_surprise(&fi("2024.06", Some(100)), &fi("2024.06", None), DEFAULT_SURPRISE_THRESHOLD), None);
+		assert_eq!(classify_surprise(&fi("2024.06", None), &fi("2024.06", Some(100)), DEFAULT_SURPRISE_THRESHOLD), None);
+	}
+
+	#[test]
+	fn latest_quarter_with_its_estimate() {
+		let quarters = [fi("2024.06", Some(130)), fi("2024.03", Some(60))];
+		let estimates = [
+			estimate(FiTerm::Quarter, "2024.03", 100, "2024-03-20"),
+			estimate(FiTerm::Quarter, "2024.06", 100, "2024-06-01"),
+			// 발표 직전의 마지막 컨센서스
+			estimate(FiTerm::Quarter, "2024.06", 120, "2024-07-01"),
+			// 다른 기간, 연간 추정치는 쓰지 않는다
+			estimate(FiTerm::Quarter, "2024.09", 200, "2024-07-15"),
+			estimate(FiTerm::Annual, "2024.06", 500, "2024-07-15"),
+		];
+		let surprise = latest_quarter_surprise(&quarters, &estimates, DEFAULT_SURPRISE_THRESHOLD).unwrap();
+		assert_eq!(surprise.year_month, YearMonth::new(2024, 6));
+		assert_eq!(surprise.estimate, Amount::from(120));
+		assert_eq!(surprise.kind, SurpriseKind::InLine);
+
+		// 최근 분기의 컨센서스가 없으면 판정하지 않는다
+		assert_eq!(latest_quarter_surprise(&quarters, &estimates[..1], DEFAULT_SURPRISE_THRESHOLD), None);
+		assert_eq!(latest_quarter_surprise(&[], &estimates, DEFAULT_SURPRISE_THRESHOLD), None);
+	}
+
+	#[test]
+	fn year_and_quarter_over() {
+		let quarters = [fi("2023.06", Some(100)), fi("2024.03", Some(80)), fi("2024.06", Some(120))];
+		let yoy = year_over_year(&quarters).unwrap();
+		assert_eq!((yoy.base_year_month, yoy.year_month), (YearMonth::new(2023, 6), YearMonth::new(2024, 6)));
+		assert_eq!(yoy.profit, Growth::Rate(20.0));
+		let qoq = quarter_over_quarter(&quarters).unwrap();
+		assert_eq!(qoq.base_year_month, YearMonth::new(2024, 3));
+		assert_eq!(qoq.profit, Growth::Rate(50.0));
+	}
+
+	#[test]
+	fn growth_without_comparison_period() {
+		// 최근 기간 기준으로 비교 기간이 없으면 더 이전 기간으로 대신하지 않는다
+		let quarters = [fi("2023.03", Some(100)), fi("2023.12", Some(80)), fi("2024.06", Some(120))];
+		assert_eq!(year_over_year(&quarters), None);
+		assert_eq!(quarter_over_quarter(&quarters), None);
+		assert_eq!(year_over_year(&[]), None);
+	}
+}
@@ -0,0 +1,13 @@
+mod basis;
+mod growth;
+mod quarters;
+mod srim;
+mod ttm;
+mod valuation;
+
+pub use basis::select_basis;
+pub use growth::{DEFAULT_SURPRISE_THRESHOLD, EarningsSurprise, Growth, GrowthRates, SurpriseKind, classify_surprise, latest_quarter_surprise, quarter_over_quarter, year_over_year};
+pub use quarters::derive_missing_quarters;
+pub use srim::{Srim, SrimConfig, SrimValue, srim, srim_from_financials};
+pub use ttm::{Ttm, query_ttm, trailing_twelve_months};
+pub use valuation::{EarningsBasis, Ratio, Valuation, ValuationInput, valuate};
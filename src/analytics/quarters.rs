@@ -0,0 +1,144 @@
+use crate::{entities::{FinancialInfo, FiscalMonth}, types::{Amount, FiscalCalendar, YearMonth}};
+
+/// 연간 실적과 나머지 세 분기의 발표된 실적이 있으면, 빠진 분기의 매출액, 영업이익, 당기순이익을 계산한다.
+///
+/// 대개 따로 발표되지 않는 4분기를 채우기 위한 것으로, 계산된 실적은 `derived` 로 표시된다.
+/// 세 분기 중 하나라도 값이 없는 항목은 계산하지 않는다.
+/// 결산월을 바꾼 해처럼 12개월이 아닌 회계연도는 건너뛴다. 분기 실적은 연간 실적과 연결/별도 구분이 같은 것만 쓴다.
+/// 저장된 결산월(`fiscal_month`)이 있으면 그 결산월로 바뀐 뒤에 다른 달로 끝나는 연간 실적도 건너뛴다.
+pub fn derive_missing_quarters(annuals: &[FinancialInfo], quarters: &[FinancialInfo], fiscal_month: Option<&FiscalMonth>) -> Vec<FinancialInfo> {
+	let mut derived_list = Vec::<FinancialInfo>::new();
+	for annual in annuals.iter() {
+		if fiscal_month.is_some_and(|fm| annual.year_month >= fm.since && annual.year_month.month != fm.month) {
+			log::debug!("Annual {} of stock `{}` doesn't end in the fiscal month", annual.year_month, annual.stock_code);
+			continue;
+		}
+		let previous = annuals.iter()
+			.filter(|fi| fi.basis == annual.basis && fi.year_month < annual.year_month)
+			.max_by_key(|fi| fi.year_month);
+		if previous.is_some_and(|prev| annual.year_month.sub_months(12) != prev.year_month) {
+			continue;
+		}
+
+		let quarter_months = fiscal_quarter_months(annual.year_month);
+		let reported: Vec<&FinancialInfo> = quarter_months.iter()
+			.filter_map(|ym| quarters.iter().find(|q| q.year_month == *ym && q.basis == annual.basis && !q.derived))
+			.collect();
+		if reported.len() != 3 {
+			continue;
+		}
+		let Some(missing) = quarter_months.into_iter().find(|ym| reported.iter().all(|q| q.year_month != *ym)) else {
+			continue
+		};
+
+		let remainder = |annual_value: Option<Amount>, value: fn(&FinancialInfo) -> Option<Amount>| {
+			reported.iter().try_fold(annual_value?, |acc, q| Some(acc - value(q)?))
+		};
+		let derived = FinancialInfo {
+			stock_code: annual.stock_code.clone(),
+			year_month: missing,
+			basis: annual.basis,
+			sales: remainder(annual.sales, |q| q.sales),
+			profit: remainder(annual.profit, |q| q.profit),
+			net_income: remainder(annual.net_income, |q| q.net_income),
+			derived: true,
+			..Default::default()
+		};
+		if derived.sales.is_some() || derived.profit.is_some() || derived.net_income.is_some() {
+			derived_list.push(derived);
+		}
+	}
+	derived_list
+}
+
+/// 결산월로 끝나는 회계연도의 분기말 년월 (오래된 순)
+fn fiscal_quarter_months(fiscal_end: YearMonth) -> [YearMonth; 4] {
+	let fourth = FiscalCalendar { fiscal_month: fiscal_end.month }.quarter_of(fiscal_end);
+	[-3, -2, -1, 0].map(|quarters| fourth.add_quarters(quarters).end())
+}
+
+#[cfg(test)]
+mod tests {
+	use crate::types::StatementBasis;
+
+	use super::*;
+
+	fn fi(year_month: &str, sales: i64, profit: i64, net_income: Option<i64>) -> FinancialInfo {
+		FinancialInfo {
+			stock_code: "005930".to_string(),
+			year_month: year_month.parse().unwrap(),
+			sales: Some(Amount::from(sales)),
+			profit: Some(Amount::from(profit)),
+			net_income: net_income.map(Amount::from),
+			..Default::default()
+		}
+	}
+
+	#[test]
+	fn derive_fourth_quarter() {
+		let annuals = [fi("2023.12", 100, 20, Some(10))];
+		let quarters = [fi("2023.03", 20, 5, Some(2)), fi("2023.06", 25, 5, Some(3)), fi("2023.09", 30, 5, None)];
+		let derived = derive_missing_quarters(&annuals, &quarters, None);
+		assert_eq!(derived.len(), 1);
+		assert_eq!(derived[0].year_month, YearMonth::new(2023, 12));
+		assert_eq!(derived[0].sales, Some(Amount::from(25)));
+		assert_eq!(derived[0].profit, Some(Amount::from(5)));
+		// 한 분기라도 값이 없는 항목은 계산하지 않는다
+		assert_eq!(derived[0].net_income, None);
+		assert!(derived[0].derived);
+	}
+
+	#[test]
+	fn derive_missing_first_quarter_of_march_fiscal_year() {
+		let annuals = [fi("2024.03", 100, 20, Some(10))];
+		let quarters = [fi("2023.09", 25, 5, Some(3)), fi("2023.12", 30, 5, Some(3)), fi("2024.03", 20, 5, Some(2))];
+		let derived = derive_missing_quarters(&annuals, &quarters, None);
+		assert_eq!(derived.len(), 1);
+		assert_eq!(derived[0].year_month, YearMonth::new(2023, 6));
+		assert_eq!(derived[0].net_income, Some(Amount::from(2)));
+	}
+
+	#[test]
+	fn skip_when_two_quarters_missing() {
+		let annuals = [fi("2023.12", 100, 20, Some(10))];
+		let quarters = [fi("2023.03", 20, 5, Some(2)), fi("2023.06", 25, 5, Some(3))];
+		assert!(derive_missing_quarters(&annuals, &quarters, None).is_empty());
+	}
+
+	#[test]
+	fn derived_quarters_are_not_reported() {
+		let annuals = [fi("2023.12", 100, 20, Some(10))];
+		let mut derived_q2 = fi("2023.06", 25, 5, Some(3));
+		derived_q2.derived = true;
+		let quarters = [fi("2023.03", 20, 5, Some(2)), derived_q2, fi("2023.09", 30, 5, Some(3))];
+		assert!(derive_missing_quarters(&annuals, &quarters, None).is_empty());
+	}
+
+	#[test]
+	fn skip_short_transition_year() {
+		// 3월 결산에서 12월 결산으로 바꾼 2022년은 9개월짜리 회계연도이다
+		let annuals = [fi("2022.03", 100, 20, Some(10)), fi("2022.12", 80, 16, Some(8))];
+		let quarters = [fi("2022.03", 25, 5, Some(2)), fi("2022.06", 25, 5, Some(2)), fi("2022.09", 25, 5, Some(2))];
+		assert!(derive_missing_quarters(&annuals, &quarters, None).is_empty());
+	}
+
+	#[test]
+	fn skip_annuals_not_ending_in_stored_fiscal_month() {
+		let fiscal_month = FiscalMonth { stock_code: "005930".to_string(), month: 12, since: YearMonth::new(2020, 12) };
+		let annuals = [fi("2023.06", 100, 20, Some(10)), fi("2024.06", 100, 20, Some(10))];
+		let quarters = [fi("2023.09", 25, 5, Some(2)), fi("2023.12", 30, 5, Some(3)), fi("2024.03", 20, 5, Some(2))];
+		assert_eq!(derive_missing_quarters(&annuals, &quarters, None).len(), 1);
+		// 12월 결산 종목의 6월 연간 실적은 잘못 들어온 것으로 본다
+		assert!(derive_missing_quarters(&annuals, &quarters, Some(&fiscal_month)).is_empty());
+	}
+
+	#[test]
+	fn quarters_of_other_basis_are_ignored() {
+		let annuals = [fi("2023.12", 100, 20, Some(10))];
+		let quarters: Vec<FinancialInfo> = [fi("2023.03", 20, 5, Some(2)), fi("2023.06", 25, 5, Some(3)), fi("2023.09", 30, 5, Some(3))]
+			.into_iter()
+			.map(|q| FinancialInfo { basis: StatementBasis::Separate, ..q })
+			.collect();
+		assert!(derive_missing_quarters(&annuals, &quarters, None).is_empty());
+	}
+}
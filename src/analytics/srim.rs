@@ -0,0 +1,178 @@
+use std::env;
+
+use rust_decimal::prelude::ToPrimitive;
+
+use crate::{entities::FinancialInfo, types::Amount};
+
+#[derive(Debug, Clone)]
+pub struct SrimConfig {
+	/// 요구수익률(%). 보통 BBB- 회사채 5년 수익률을 쓴다
+	pub required_return: f64,
+	/// 초과이익 지속계수. 1.0 미만이면 초과이익이 매년 그 비율로 줄어든다
+	pub persistences: Vec<f64>,
+}
+
+impl Default for SrimConfig {
+	fn default() -> Self {
+		Self {
+			required_return: 8.0,
+			persistences: vec![1.0, 0.9, 0.8],
+		}
+	}
+}
+
+impl SrimConfig {
+	pub fn from_env() -> Self {
+		let mut conf = Self::default();
+		if let Some(required_return) = env::var("SRIM_REQUIRED_RETURN").ok().and_then(|s| s.parse::<f64>().ok()) {
+			conf.required_return = required_return;
+		}
+		// 예: `1.0,0.9,0.8`
+		if let Ok(persistences) = env::var("SRIM_PERSISTENCES") {
+			let persistences: Vec<f64> = persistences.split(',')
+				.filter_map(|s| s.trim().parse::<f64>().ok())
+				.filter(|w| (0.0..=1.0).contains(w))
+				.collect();
+			if !persistences.is_empty() {
+				conf.persistences = persistences;
+			}
+		}
+		conf
+	}
+}
+
+/// S-RIM 으로 계산한 주당 적정가치
+#[derive(Debug, Clone, PartialEq)]
+pub struct SrimValue {
+	/// 초과이익 지속계수
+	pub persistence: f64,
+	/// 적정주가(원)
+	pub value: f64,
+	/// 종가의 적정주가 대비 할인율(%). 양수이면 적정주가보다 싸다
+	pub discount: Option<f64>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Srim {
+	/// BPS(원)
+	pub bps: f64,
+	/// ROE(%)
+	pub roe: f64,
+	/// 요구수익률(%)
+	pub required_return: f64,
+	/// `SrimConfig::persistences` 순서의 적정가치
+	pub values: Vec<SrimValue>,
+}
+
+/// BPS + BPS × (ROE − r) × w / (1 + r − w)
+///
+/// w = 1 이면 초과이익이 영원히 지속되는 기본식 BPS + BPS × (ROE − r) / r 과 같다.
+/// BPS 가 0 이하(자본잠식)이거나 요구수익률이 0 이하이면 `None`.
+pub fn srim(bps: Amount, roe: Amount, price: Option<u32>, conf: &SrimConfig) -> Option<Srim> {
+	let (bps, roe, r) = (bps.to_f64()?, roe.to_f64()? / 100.0, conf.required_return / 100.0);
+	if bps <= 0.0 || r <= 0.0 {
+		return None;
+	}
+
+	let excess_earnings = bps * (roe - r);
+	let values = conf.persistences.iter()
+		.map(|&w| {
+			let value = bps + excess_earnings * w / (1.0 + r - w);
+			let discount = price
+				.filter(|_| value > 0.0)
+				.map(|price| (value - price as f64) / value * 100.0);
+			SrimValue { persistence: w, value, discount }
+		})
+		.collect();
+	Some(Srim {
+		bps,
+		roe: roe * 100.0,
+		required_return: conf.required_return,
+		values,
+	})
+}
+
+/// 컨센서스 ROE 가 있으면 그것을, 없으면 최근 연간 ROE 를 쓴다. BPS 는 가장 최근 분기, 없으면 최근 연간 값
+pub fn srim_from_financials(annuals: &[FinancialInfo], quarters: &[FinancialInfo], annual_estimate: Option<&FinancialInfo>, price: Option<u32>, conf: &SrimConfig) -> Option<Srim> {
+	let latest_annual = annuals.iter().max_by_key(|fi| fi.year_month);
+	let latest_quarter = quarters.iter().filter(|fi| fi.bps.is_some()).max_by_key(|fi| fi.year_month);
+	let roe = annual_estimate.and_then(|fi| fi.roe)
+		.or_else(|| latest_annual.and_then(|fi| fi.roe))?;
+	let bps = latest_quarter.and_then(|fi| fi.bps)
+		.or_else(|| latest_annual.and_then(|fi| fi.bps))?;
+	srim(bps, roe, price, conf)
+}
+
+#[cfg(test)]
+mod tests {
+	use crate::types::YearMonth;
+
+	use super::*;
+
+	fn assert_close(actual: f64, expected: f64) {
+		assert!((actual - expected).abs() < 1e-6, "{actual} != {expected}");
+	}
+
+	#[test]
+	fn values_by_persistence() {
+		let conf = SrimConfig::default();
+		let srim = srim(Amount::from(10000), Amount::from(12), Some(12000), &conf).unwrap();
+		// 초과이익 = 10000 × (12% − 8%) = 400
+		assert_eq!(srim.values.len(), 3);
+		// w = 1: 10000 + 400 / 0.08
+		assert_close(srim.values[0].value, 15000.0);
+		assert_close(srim.values[0].discount.unwrap(), 20.0);
+		// w = 0.9: 10000 + 400 × 0.9 / 0.18
+		assert_close(srim.values[1].value, 12000.0);
+		assert_close(srim.values[1].discount.unwrap(), 0.0);
+		// w = 0.8: 10000 + 400 × 0.8 / 0.28
+		assert_close(srim.values[2].value, 10000.0 + 320.0 / 0.28);
+	}
+
+	#[test]
+	fn roe_below_required_return() {
+		let conf = SrimConfig { required_return: 8.0, persistences: vec![1.0, 0.5] };
+		let srim = srim(Amount::from(10000), Amount::from(6), None, &conf).unwrap();
+		// 초과이익 = −200
+		assert_close(srim.values[0].value, 7500.0);
+		// w = 0.5: 10000 − 200 × 0.5 / 0.58
+		assert_close(srim.values[1].value, 10000.0 - 100.0 / 0.58);
+		assert_eq!(srim.values[1].discount, None);
+	}
+
+	#[test]
+	fn impaired_capital() {
+		let conf = SrimConfig::default();
+		assert_eq!(srim(Amount::ZERO, Amount::from(12), None, &conf), None);
+		assert_eq!(srim(Amount::from(-100), Amount::from(12), None, &conf), None);
+	}
+
+	#[test]
+	fn prefer_estimate_roe_and_quarter_bps() {
+		let conf = SrimConfig { required_return: 10.0, persistences: vec![1.0] };
+		let annual = FinancialInfo {
+			year_month: YearMonth::new(2023, 12),
+			roe: Some(Amount::from(5)),
+			bps: Some(Amount::from(9000)),
+			..Default::default()
+		};
+		let quarter = FinancialInfo {
+			year_month: YearMonth::new(2024, 3),
+			bps: Some(Amount::from(10000)),
+			..Default::default()
+		};
+		let estimate = FinancialInfo {
+			year_month: YearMonth::new(2024, 12),
+			roe: Some(Amount::from(15)),
+			..Default::default()
+		};
+		let srim = srim_from_financials(std::slice::from_ref(&annual), &[quarter], Some(&estimate), None, &conf).unwrap();
+		assert_close(srim.bps, 10000.0);
+		assert_close(srim.roe, 15.0);
+		assert_close(srim.values[0].value, 15000.0);
+
+		let srim = srim_from_financials(&[annual], &[], None, None, &conf).unwrap();
+		assert_close(srim.bps, 9000.0);
+		assert_close(srim.values[0].value, 4500.0);
+	}
+}
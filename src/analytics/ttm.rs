@@ -0,0 +1,134 @@
+use crate::{analytics::select_basis, entities::{FiQuartersDao, FinancialInfo}, types::{Amount, Error, StatementBasis, YearMonth}};
+
+/// 최근 4개 분기 합산 실적 (Trailing Twelve Months)
+#[derive(Debug, Clone, PartialEq)]
+pub struct Ttm {
+	pub stock_code: String,
+	/// 합산한 마지막 분기
+	pub year_month: YearMonth,
+	/// 매출액
+	pub sales: Option<Amount>,
+	/// 영업이익
+	pub profit: Option<Amount>,
+	/// 당기순이익
+	pub net_income: Option<Amount>,
+	/// 연간 실적에서 계산한 분기가 포함되었는지
+	pub includes_derived: bool,
+	/// 4개 분기 중 없는 분기. 하나라도 있으면 합산값은 모두 `None`
+	pub missing: Vec<YearMonth>,
+}
+
+impl Ttm {
+	pub fn is_complete(&self) -> bool {
+		self.missing.is_empty()
+	}
+}
+
+/// 가장 최근 분기부터 연속된 4개 분기의 매출액, 영업이익, 당기순이익을 합산한다.
+///
+/// 항목별로 한 분기라도 값이 없으면 그 항목은 `None` 이다. 분기 실적이 없으면 `None`.
+pub fn trailing_twelve_months(quarters: &[FinancialInfo]) -> Option<Ttm> {
+	let latest = quarters.iter().max_by_key(|q| q.year_month)?;
+
+	let mut included = Vec::<&FinancialInfo>::with_capacity(4);
+	let mut missing = Vec::<YearMonth>::new();
+	for months in [9, 6, 3, 0] {
+		let ym = latest.year_month.sub_months(months);
+		match quarters.iter().find(|q| q.year_month == ym) {
+			Some(q) => included.push(q),
+			None => missing.push(ym),
+		}
+	}
+
+	let sum = |value: fn(&FinancialInfo) -> Option<Amount>| {
+		if !missing.is_empty() {
+			return None;
+		}
+		included.iter().try_fold(Amount::ZERO, |acc, q| Some(acc + value(q)?))
+	};
+	Some(Ttm {
+		stock_code: latest.stock_code.clone(),
+		year_month: latest.year_month,
+		sales: sum(|q| q.sales),
+		profit: sum(|q| q.profit),
+		net_income: sum(|q| q.net_income),
+		includes_derived: included.iter().any(|q| q.derived),
+		missing,
+	})
+}
+
+/// `basis` 구분의 분기 실적으로 합산한다. 없는 분기는 다른 구분의 실적을 쓴다
+pub async fn query_ttm(dao: &(dyn FiQuartersDao + Sync), stock_code: &str, basis: StatementBasis) -> Result<Option<Ttm>, Error> {
+	let quarters = select_basis(dao.list(stock_code).await?, basis);
+	Ok(trailing_twelve_months(&quarters))
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	fn quarter(year_month: &str, sales: Option<i64>, profit: i64, net_income: i64) -> FinancialInfo {
+		FinancialInfo {
+			stock_code: "005930".to_string(),
+			year_month: year_month.parse().unwrap(),
+			sales: sales.map(Amount::from),
+			profit: Some(Amount::from(profit)),
+			net_income: Some(Amount::from(net_income)),
+			..Default::default()
+		}
+	}
+
+	#[test]
+	fn sum_last_four_quarters() {
+		let mut q4 = quarter("2023.12", Some(40), 4, 2);
+		q4.derived = true;
+		let quarters = [
+			quarter("2023.03", Some(100), 100, 100),
+			quarter("2023.06", Some(10), 1, 1),
+			quarter("2023.09", Some(20), 2, 1),
+			q4,
+			quarter("2024.03", Some(30), 3, 1),
+		];
+		let ttm = trailing_twelve_months(&quarters).unwrap();
+		assert_eq!(ttm.year_month, YearMonth::new(2024, 3));
+		assert_eq!(ttm.sales, Some(Amount::from(100)));
+		assert_eq!(ttm.profit, Some(Amount::from(10)));
+		assert_eq!(ttm.net_income, Some(Amount::from(5)));
+		assert!(ttm.includes_derived);
+		assert!(ttm.is_complete());
+	}
+
+	#[test]
+	fn missing_quarter() {
+		let quarters = [
+			quarter("2023.06", Some(10), 1, 1),
+			quarter("2023.12", Some(40), 4, 2),
+			quarter("2024.03", Some(30), 3, 1),
+		];
+		let ttm = trailing_twelve_months(&quarters).unwrap();
+		assert_eq!(ttm.missing, vec![YearMonth::new(2023, 9)]);
+		assert!(!ttm.is_complete());
+		assert_eq!(ttm.sales, None);
+		assert_eq!(ttm.profit, None);
+		assert!(!ttm.includes_derived);
+	}
+
+	#[test]
+	fn missing_value_of_one_item() {
+		let quarters = [
+			quarter("2023.06", Some(10), 1, 1),
+			quarter("2023.09", None, 2, 1),
+			quarter("2023.12", Some(40), 4, 2),
+			quarter("2024.03", Some(30), 3, 1),
+		];
+		let ttm = trailing_twelve_months(&quarters).unwrap();
+		assert!(ttm.is_complete());
+		assert_eq!(ttm.sales, None);
+		assert_eq!(ttm.profit, Some(Amount::from(10)));
+	}
+
+	#[test]
+	fn no_quarters() {
+		assert_eq!(trailing_twelve_months(&[]), None);
+	}
+}
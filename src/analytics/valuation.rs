@@ -0,0 +1,265 @@
+use rust_decimal::prelude::ToPrimitive;
+use serde::{Serialize, Serializer};
+
+use crate::{analytics::Ttm, entities::FinancialInfo};
+
+/// 억원
+const KRW_PER_100M: f64 = 100_000_000.0;
+
+/// 계산된 투자지표
+#[derive(Debug, Default, Copy, Clone, PartialEq)]
+pub enum Ratio {
+	Value(f64),
+	/// 분모가 0 이하라 의미 없는 값 (적자, 자본잠식 등)
+	NotMeaningful,
+	/// 입력값이 없어 계산할 수 없음
+	#[default]
+	Unavailable,
+}
+
+impl Ratio {
+	pub fn value(&self) -> Option<f64> {
+		match self {
+			Ratio::Value(v) => Some(*v),
+			_ => None,
+		}
+	}
+
+	/// `numerator / denominator`. 분모가 0 이하이면 `NotMeaningful`
+	fn of(numerator: Option<f64>, denominator: Option<f64>) -> Self {
+		match (numerator, denominator) {
+			(Some(_), Some(den)) if den <= 0.0 => Ratio::NotMeaningful,
+			(Some(num), Some(den)) => Ratio::Value(num / den),
+			_ => Ratio::Unavailable,
+		}
+	}
+
+	fn percent(self) -> Self {
+		match self {
+			Ratio::Value(v) => Ratio::Value(v * 100.0),
+			other => other,
+		}
+	}
+}
+
+/// CSV 에는 값은 소수점 둘째 자리까지, 의미 없는 값은 `N/M`, 없는 값은 빈 칸으로 쓴다
+impl Serialize for Ratio {
+	fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+		match self {
+			Ratio::Value(v) => serializer.serialize_f64((v * 100.0).round() / 100.0),
+			Ratio::NotMeaningful => serializer.serialize_str("N/M"),
+			Ratio::Unavailable => serializer.serialize_none(),
+		}
+	}
+}
+
+/// 손익 항목을 가져온 실적
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum EarningsBasis {
+	/// 최근 4개 분기 합산
+	Ttm,
+	/// 최근 연간 실적
+	Annual,
+}
+
+impl EarningsBasis {
+	pub fn as_str(&self) -> &'static str {
+		match self {
+			EarningsBasis::Ttm => "TTM",
+			EarningsBasis::Annual => "ANNUAL",
+		}
+	}
+}
+
+pub struct ValuationInput<'a> {
+	/// 종가(원)
+	pub price: Option<u32>,
+	/// 상장주식수
+	pub shares: Option<u64>,
+	pub ttm: Option<&'a Ttm>,
+	/// 최근 연간 실적. TTM 이 불완전할 때의 손익과 BPS, 주당배당금에 쓴다
+	pub annual: Option<&'a FinancialInfo>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Valuation {
+	/// 시가총액(억원)
+	pub market_cap: Option<f64>,
+	pub basis: Option<EarningsBasis>,
+	/// 시가총액 / 당기순이익
+	pub per: Ratio,
+	/// 종가 / BPS
+	pub pbr: Ratio,
+	/// 시가총액 / 매출액
+	pub psr: Ratio,
+	/// 당기순이익 / 시가총액 (%). 적자이면 음수
+	pub earnings_yield: Ratio,
+	/// 주당배당금 / 종가 (%)
+	pub dividend_yield: Ratio,
+	/// 시가총액 / 영업이익
+	pub por: Ratio,
+}
+
+/// 완전한 TTM 이 있으면 TTM 손익을, 없으면 최근 연간 손익을 기준으로 투자지표를 계산한다
+pub fn valuate(input: &ValuationInput) -> Valuation {
+	let price = input.price.map(|p| p as f64);
+	let market_cap = price.zip(input.shares).map(|(price, shares)| price * shares as f64 / KRW_PER_100M);
+
+	let ttm = input.ttm.filter(|ttm| ttm.is_complete());
+	let (basis, sales, profit, net_income) = match (ttm, input.annual) {
+		(Some(ttm), _) => (Some(EarningsBasis::Ttm), ttm.sales, ttm.profit, ttm.net_income),
+		(None, Some(annual)) => (Some(EarningsBasis::Annual), annual.sales, annual.profit, annual.net_income),
+		(None, None) => (None, None, None, None),
+	};
+	let sales = sales.and_then(|v| v.to_f64());
+	let profit = profit.and_then(|v| v.to_f64());
+	let net_income = net_income.and_then(|v| v.to_f64());
+	let bps = input.annual.and_then(|fi| fi.bps).and_then(|v| v.to_f64());
+	let dividend = input.annual.and_then(|fi| fi.dividend).and_then(|v| v.to_f64());
+
+	Valuation {
+		market_cap,
+		basis,
+		per: Ratio::of(market_cap, net_income),
+		pbr: Ratio::of(price, bps),
+		psr: Ratio::of(market_cap, sales),
+		earnings_yield: Ratio::of(net_income, market_cap).percent(),
+		dividend_yield: Ratio::of(dividend, price).percent(),
+		por: Ratio::of(market_cap, profit),
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use rust_decimal::Decimal;
+
+	use crate::types::YearMonth;
+
+	use super::*;
+
+	fn annual(net_income: Option<i64>, bps: Option<i64>) -> FinancialInfo {
+		FinancialInfo {
+			stock_code: "005930".to_string(),
+			year_month: YearMonth::new(2023, 12),
+			sales: Some(Decimal::from(2000)),
+			profit: Some(Decimal::from(100)),
+			net_income: net_income.map(Decimal::from),
+			bps: bps.map(Decimal::from),
+			dividend: Some(Decimal::from(200)),
+			..Default::default()
+		}
+	}
+
+	fn ttm(net_income: i64, missing: Vec<YearMonth>) -> Ttm {
+		Ttm {
+			stock_code: "005930".to_string(),
+			year_month: YearMonth::new(2024, 3),
+			sales: Some(Decimal::from(2400)),
+			profit: Some(Decimal::from(160)),
+			net_income: Some(Decimal::from(net_income)),
+			includes_derived: false,
+			missing,
+		}
+	}
+
+	/// 종가 10,000원, 1,000만주 → 시가총액 1,000억원
+	fn valuate_with(annual: Option<&FinancialInfo>, ttm: Option<&Ttm>) -> Valuation {
+		valuate(&ValuationInput { price: Some(10_000), shares: Some(10_000_000), ttm, annual })
+	}
+
+	fn assert_value(ratio: Ratio, expected: f64) {
+		match ratio {
+			Ratio::Value(v) => assert!((v - expected).abs() < 1e-9, "{v} != {expected}"),
+			other => panic!("{other:?} != Value({expected})"),
+		}
+	}
+
+	#[test]
+	fn annual_earnings() {
+		let annual = annual(Some(50), Some(5000));
+		let valuation = valuate_with(Some(&annual), None);
+		assert_eq!(valuation.market_cap, Some(1000.0));
+		assert_eq!(valuation.basis, Some(EarningsBasis::Annual));
+		assert_value(valuation.per, 20.0);
+		assert_value(valuation.pbr, 2.0);
+		assert_value(valuation.psr, 0.5);
+		assert_value(valuation.por, 10.0);
+		assert_value(valuation.earnings_yield, 5.0);
+		assert_value(valuation.dividend_yield, 2.0);
+	}
+
+	#[test]
+	fn complete_ttm_over_annual() {
+		let annual = annual(Some(50), Some(5000));
+		let complete = ttm(80, Vec::new());
+		let valuation = valuate_with(Some(&annual), Some(&complete));
+		assert_eq!(valuation.basis, Some(EarningsBasis::Ttm));
+		assert_value(valuation.per, 12.5);
+		assert_value(valuation.psr, 1000.0 / 2400.0);
+		// BPS 와 배당은 연간 실적
+		assert_value(valuation.pbr, 2.0);
+
+		let incomplete = ttm(80, vec![YearMonth::new(2023, 9)]);
+		let valuation = valuate_with(Some(&annual), Some(&incomplete));
+		assert_eq!(valuation.basis, Some(EarningsBasis::Annual));
+		assert_value(valuation.per, 20.0);
+	}
+
+	#[test]
+	fn negative_or_zero_earnings() {
+		let deficit = annual(Some(-50), Some(5000));
+		let valuation = valuate_with(Some(&deficit), None);
+		assert_eq!(valuation.per, Ratio::NotMeaningful);
+		// 이익수익률은 분모가 시가총액이라 적자도 값이 있다
+		assert_value(valuation.earnings_yield, -5.0);
+
+		let zero = annual(Some(0), Some(5000));
+		let valuation = valuate_with(Some(&zero), None);
+		assert_eq!(valuation.per, Ratio::NotMeaningful);
+		assert_value(valuation.earnings_yield, 0.0);
+	}
+
+	#[test]
+	fn negative_or_zero_equity() {
+		let impaired = annual(Some(50), Some(-100));
+		assert_eq!(valuate_with(Some(&impaired), None).pbr, Ratio::NotMeaningful);
+		let zero = annual(Some(50), Some(0));
+		assert_eq!(valuate_with(Some(&zero), None).pbr, Ratio::NotMeaningful);
+	}
+
+	#[test]
+	fn missing_inputs() {
+		let no_earnings = annual(None, None);
+		let valuation = valuate_with(Some(&no_earnings), None);
+		assert_eq!(valuation.per, Ratio::Unavailable);
+		assert_eq!(valuation.pbr, Ratio::Unavailable);
+		assert_eq!(valuation.earnings_yield, Ratio::Unavailable);
+		assert_value(valuation.psr, 0.5);
+
+		let valuation = valuate_with(None, None);
+		assert_eq!(valuation.basis, None);
+		assert_eq!(valuation.per, Ratio::Unavailable);
+		assert_eq!(valuation.dividend_yield, Ratio::Unavailable);
+
+		let annual = annual(Some(50), Some(5000));
+		let valuation = valuate(&ValuationInput { price: None, shares: Some(10_000_000), ttm: None, annual: Some(&annual) });
+		assert_eq!(valuation.market_cap, None);
+		assert_eq!(valuation.per, Ratio::Unavailable);
+		assert_eq!(valuation.pbr, Ratio::Unavailable);
+	}
+
+	#[test]
+	fn zero_market_cap() {
+		let annual = annual(Some(50), Some(5000));
+		let valuation = valuate(&ValuationInput { price: Some(10_000), shares: Some(0), ttm: None, annual: Some(&annual) });
+		assert_eq!(valuation.market_cap, Some(0.0));
+		assert_eq!(valuation.earnings_yield, Ratio::NotMeaningful);
+	}
+
+	#[test]
+	fn serialize_ratio() {
+		assert_eq!(serde_json::to_string(&Ratio::Value(12.345)).unwrap(), "12.35");
+		assert_eq!(serde_json::to_string(&Ratio::NotMeaningful).unwrap(), "\"N/M\"");
+		assert_eq!(serde_json::to_string(&Ratio::Unavailable).unwrap(), "null");
+	}
+}
@@ -0,0 +1,125 @@
+use std::{fs::File, ops::Range};
+
+use chrono::{Duration, Local, NaiveDate};
+use csv::{QuoteStyle, WriterBuilder};
+use rust_decimal::Decimal;
+use serde::Serialize;
+use stock_fn_scraper::{entities::{EntityDao, ProductItem, StockPrice, StockPriceRange}, repository::Repo, types::{Error, Market}};
+
+use crate::header;
+
+struct EtfData {
+	item: ProductItem,
+	price_latest: Option<StockPrice>,
+	price_range: Option<StockPriceRange>,
+}
+
+pub async fn export(repo: &Repo) -> Result<(), Error> {
+	let today = Local::now().date_naive();
+	let items = repo.product_items().list(&[Market::ETF]).await?;
+
+	let mut data_list = Vec::<EtfData>::new();
+	for item in items.into_iter() {
+		if today - item.info_date > Duration::days(10) {
+			log::info!("ETF `{}|{}` Outdated and skipped (date:{})", item.code, item.name, item.info_date);
+			continue;
+		}
+
+		let code = item.code.clone();
+		let name = item.name.clone();
+		match fetch_data(item, repo).await {
+			Ok(data) => data_list.push(data),
+			Err(err) => log::error!("Failed to fetch data for ETF `{}|{}': {}", code, name, err),
+		}
+	}
+
+	create_csv(&data_list)
+}
+
+async fn fetch_data(item: ProductItem, repo: &Repo) -> Result<EtfData, Error> {
+	let price_latest = repo.stock_prices().latest(&item.code).await?;
+	let recent_year = {
+		let end_date = item.info_date + Duration::days(1);
+		let start_date = end_date - Duration::days(365);
+		Range { start: start_date, end: end_date }
+	};
+	let price_range = repo.stock_prices().range(&item.code, recent_year).await?;
+	Ok(EtfData { item, price_latest, price_range })
+}
+
+#[derive(Default, Serialize)]
+struct EtfRecord {
+	#[serde(rename = "종목코드")]
+	code: String,
+	#[serde(rename = "종목명")]
+	name: String,
+	#[serde(rename = "기준일자")]
+	date: NaiveDate,
+	#[serde(rename = "종가")]
+	price: Option<u32>,
+	#[serde(rename = "시가총액(억)")]
+	market_cap: Option<u32>,
+	#[serde(rename = "52주 최고가")]
+	highest_in_year: Option<u32>,
+	#[serde(rename = "52주 최저가")]
+	lowest_in_year: Option<u32>,
+	#[serde(rename = "기초지수명")]
+	obj_idx: Option<String>,
+	#[serde(rename = "지수산출기관")]
+	idx_inst: Option<String>,
+	#[serde(rename = "추적배수")]
+	idx_multiplier: Option<i32>,
+	#[serde(rename = "복제방법")]
+	replica_method: Option<String>,
+	#[serde(rename = "기초시장분류")]
+	idx_market: Option<String>,
+	#[serde(rename = "기초자산분류")]
+	idx_asset: Option<String>,
+	#[serde(rename = "운용사")]
+	op_company: Option<String>,
+	#[serde(rename = "총보수(%)")]
+	fee_rate: Option<Decimal>,
+	#[serde(rename = "과세유형")]
+	tax_type: Option<String>,
+}
+
+fn create_csv(data_list: &[EtfData]) -> Result<(), Error> {
+	let file = File::create("ETF별데이터.csv")?;
+	let mut writer = WriterBuilder::new()
+		.quote_style(QuoteStyle::NonNumeric)
+		.has_headers(false)
+		.from_writer(file);
+	log::info!("Writing {} ETF data in CSV...", data_list.len());
+
+	// 내보낼 ETF 가 없어도 헤더는 쓴다
+	let headers = header::field_names(&EtfRecord::default()).map_err(|err| err.to_string())?;
+	writer.write_record(&headers)?;
+
+	for data in data_list.iter() {
+		let item = &data.item;
+		let mut rec = EtfRecord {
+			code: item.code.clone(),
+			name: item.name.clone(),
+			date: item.info_date,
+			obj_idx: item.etf_obj_idx.clone(),
+			idx_inst: item.etf_idx_inst.clone(),
+			idx_multiplier: item.etf_idx_multiplier,
+			replica_method: item.etf_replica_method.clone(),
+			idx_market: item.etf_idx_market.clone(),
+			idx_asset: item.etf_idx_asset.clone(),
+			op_company: item.etf_op_company.clone(),
+			fee_rate: item.etf_fee_rate,
+			tax_type: item.etf_tax_type.clone(),
+			..Default::default()
+		};
+		rec.price = data.price_latest.as_ref().and_then(|p| p.closing);
+		rec.market_cap = rec.price.zip(item.list_shares).map(|(price, shares)| (price as f64 * shares as f64 / 100_000_000_f64).round() as u32);
+		rec.highest_in_year = data.price_range.as_ref().and_then(|r| r.highest);
+		rec.lowest_in_year = data.price_range.as_ref().and_then(|r| r.lowest);
+
+		writer.serialize(rec)?;
+	}
+
+	writer.flush()?;
+	Ok(())
+}
@@ -0,0 +1,176 @@
+use std::fmt;
+
+use serde::{ser::{self, Impossible, SerializeStruct, SerializeTuple}, Serialize, Serializer};
+
+/// 레코드의 serde 필드 이름을 순서대로 모은다
+///
+/// 최상위는 구조체이거나 구조체의 튜플이어야 하고, 필드 값은 직렬화하지 않는다.
+pub fn field_names<T: Serialize>(row: &T) -> Result<Vec<&'static str>, HeaderError> {
+	let mut names = FieldNames(Vec::new());
+	row.serialize(&mut names)?;
+	Ok(names.0)
+}
+
+#[derive(Debug)]
+pub struct HeaderError(String);
+
+impl fmt::Display for HeaderError {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		write!(f, "Cannot build CSV headers: {}", self.0)
+	}
+}
+
+impl std::error::Error for HeaderError {}
+
+impl ser::Error for HeaderError {
+	fn custom<T: fmt::Display>(msg: T) -> Self {
+		HeaderError(msg.to_string())
+	}
+}
+
+struct FieldNames(Vec<&'static str>);
+
+macro_rules! unsupported {
+	($($method:ident($($ty:ty),*);)*) => {
+		$(
+			fn $method(self, $(_: $ty),*) -> Result<(), HeaderError> {
+				Err(HeaderError(format!("`{}` is not a record", stringify!($method))))
+			}
+		)*
+	};
+}
+
+impl Serializer for &mut FieldNames {
+	type Ok = ();
+	type Error = HeaderError;
+	type SerializeSeq = Impossible<(), HeaderError>;
+	type SerializeTuple = Self;
+	type SerializeTupleStruct = Impossible<(), HeaderError>;
+	type SerializeTupleVariant = Impossible<(), HeaderError>;
+	type SerializeMap = Impossible<(), HeaderError>;
+	type SerializeStruct = Self;
+	type SerializeStructVariant = Impossible<(), HeaderError>;
+
+	unsupported! {
+		serialize_bool(bool);
+		serialize_i8(i8);
+		serialize_i16(i16);
+		serialize_i32(i32);
+		serialize_i64(i64);
+		serialize_u8(u8);
+		serialize_u16(u16);
+		serialize_u32(u32);
+		serialize_u64(u64);
+		serialize_f32(f32);
+		serialize_f64(f64);
+		serialize_char(char);
+		serialize_str(&str);
+		serialize_bytes(&[u8]);
+		serialize_none();
+		serialize_unit();
+		serialize_unit_struct(&'static str);
+		serialize_unit_variant(&'static str, u32, &'static str);
+	}
+
+	fn serialize_some<T: ?Sized + Serialize>(self, value: &T) -> Result<(), HeaderError> {
+		value.serialize(self)
+	}
+
+	fn serialize_newtype_struct<T: ?Sized + Serialize>(self, _: &'static str, value: &T) -> Result<(), HeaderError> {
+		value.serialize(self)
+	}
+
+	fn serialize_newtype_variant<T: ?Sized + Serialize>(self, name: &'static str, _: u32, _: &'static str, _: &T) -> Result<(), HeaderError> {
+		Err(HeaderError(format!("`{name}` is an enum")))
+	}
+
+	fn serialize_seq(self, _: Option<usize>) -> Result<Self::SerializeSeq, HeaderError> {
+		Err(HeaderError("sequence is not a record".to_string()))
+	}
+
+	fn serialize_tuple(self, _: usize) -> Result<Self, HeaderError> {
+		Ok(self)
+	}
+
+	fn serialize_tuple_struct(self, name: &'static str, _: usize) -> Result<Self::SerializeTupleStruct, HeaderError> {
+		Err(HeaderError(format!("`{name}` is a tuple struct")))
+	}
+
+	fn serialize_tuple_variant(self, name: &'static str, _: u32, _: &'static str, _: usize) -> Result<Self::SerializeTupleVariant, HeaderError> {
+		Err(HeaderError(format!("`{name}` is an enum")))
+	}
+
+	fn serialize_map(self, _: Option<usize>) -> Result<Self::SerializeMap, HeaderError> {
+		Err(HeaderError("map is not a record".to_string()))
+	}
+
+	fn serialize_struct(self, _: &'static str, _: usize) -> Result<Self, HeaderError> {
+		Ok(self)
+	}
+
+	fn serialize_struct_variant(self, name: &'static str, _: u32, _: &'static str, _: usize) -> Result<Self::SerializeStructVariant, HeaderError> {
+		Err(HeaderError(format!("`{name}` is an enum")))
+	}
+}
+
+impl SerializeTuple for &mut FieldNames {
+	type Ok = ();
+	type Error = HeaderError;
+
+	fn serialize_element<T: ?Sized + Serialize>(&mut self, value: &T) -> Result<(), HeaderError> {
+		value.serialize(&mut **self)
+	}
+
+	fn end(self) -> Result<(), HeaderError> {
+		Ok(())
+	}
+}
+
+impl SerializeStruct for &mut FieldNames {
+	type Ok = ();
+	type Error = HeaderError;
+
+	fn serialize_field<T: ?Sized + Serialize>(&mut self, key: &'static str, _: &T) -> Result<(), HeaderError> {
+		self.0.push(key);
+		Ok(())
+	}
+
+	fn end(self) -> Result<(), HeaderError> {
+		Ok(())
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[derive(Default, Serialize)]
+	struct Left {
+		#[serde(rename = "종목코드")]
+		code: String,
+		#[serde(rename = "매출액(억)")]
+		sales: Option<i64>,
+	}
+
+	#[derive(Default, Serialize)]
+	struct Right {
+		#[serde(rename = "PER")]
+		per: Option<f64>,
+	}
+
+	#[test]
+	fn struct_field_names() {
+		assert_eq!(field_names(&Left::default()).unwrap(), vec!["종목코드", "매출액(억)"]);
+	}
+
+	#[test]
+	fn tuple_of_structs() {
+		let row = (Left::default(), Right::default());
+		assert_eq!(field_names(&row).unwrap(), vec!["종목코드", "매출액(억)", "PER"]);
+	}
+
+	#[test]
+	fn rejects_non_record() {
+		assert!(field_names(&42).is_err());
+	}
+}
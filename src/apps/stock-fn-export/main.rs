@@ -1,20 +1,83 @@
-use std::{collections::LinkedList, fs::File, io, ops::Range};
+use std::{collections::LinkedList, fs::File, ops::Range, str::FromStr};
 
 use chrono::{Duration, Local, NaiveDate};
 use csv::{QuoteStyle, WriterBuilder};
+use rust_decimal::prelude::ToPrimitive;
 use serde::Serialize;
-use stock_fn_scraper::{entities::{EntityDao, FinancialInfo, Stock, StockPrice, StockPriceRange}, logger, repository::{self, DatabaseConfig, Repo}, types::{Error, YearMonth}};
+use stock_fn_scraper::{analytics::{self, Growth, GrowthRates, Ratio, Srim, SrimConfig, Ttm, ValuationInput}, entities::{EntityDao, FiEstimate, FinancialInfo, Stock, StockPrice, StockPriceRange}, logger, repository::{self, DatabaseConfig, Repo}, types::{Amount, Error, FiTerm, StatementBasis, Unit, YearMonth}};
 
+mod etf;
+mod header;
 
+const USAGE: &str = "Usage: stock-fn-export [--etf | [--as-of YYYY-MM-DD] [--valuation] [--unit 원|백만원|억원] [--basis 연결|별도]]";
+
+#[derive(Default)]
+struct Args {
+	/// 주식 대신 ETF 데이터를 내보낸다
+	etf: bool,
+	/// 해당 일자에 알려져 있던 실적과 시세로 내보낸다
+	as_of: Option<NaiveDate>,
+	/// 투자지표 컬럼을 덧붙인다
+	valuation: bool,
+	/// 금액 컬럼의 단위. 기본은 억원
+	unit: Option<Unit>,
+	/// 우선할 재무제표 구분. 해당 구분의 실적이 없는 기간은 다른 구분을 쓴다. 기본은 연결
+	basis: Option<StatementBasis>,
+}
+
+impl Args {
+	fn amount_unit(&self) -> Unit {
+		self.unit.unwrap_or(FinancialInfo::AMOUNT_UNIT)
+	}
+}
+
+fn parse_args() -> Result<Args, Error> {
+	let mut args = Args::default();
+	let mut iter = std::env::args().skip(1);
+	while let Some(arg) = iter.next() {
+		let mut value = || iter.next().ok_or_else(|| Error::from(format!("Missing value for `{arg}`")));
+		match arg.as_str() {
+			"--etf" => args.etf = true,
+			"--as-of" => args.as_of = Some(NaiveDate::parse_from_str(&value()?, "%Y-%m-%d")?),
+			"--valuation" => args.valuation = true,
+			"--unit" => {
+				let unit = Unit::from_str(&value()?)?;
+				if unit.won_scale().is_none() {
+					return Err(format!("`{}` is not a currency unit", unit.label()).into());
+				}
+				args.unit = Some(unit);
+			},
+			"--basis" => args.basis = Some(StatementBasis::from_str(&value()?)?),
+			_ => return Err(format!("Unknown argument `{arg}`").into()),
+		}
+	}
+	if args.etf && (args.as_of.is_some() || args.valuation || args.unit.is_some() || args.basis.is_some()) {
+		return Err("`--as-of`, `--valuation`, `--unit` and `--basis` can't be used with `--etf`".into());
+	}
+	Ok(args)
+}
 
 #[tokio::main]
 async fn main() {
 	dotenvy::dotenv().ok();
 	logger::prepare();
 
+	let args = parse_args().unwrap_or_else(|err| {
+		eprintln!("{err}\n{USAGE}");
+		std::process::exit(2);
+	});
+
 	let db_conf = DatabaseConfig::from_env();
 	let repo = repository::create(&db_conf).await;
 
+	if args.etf {
+		etf::export(&repo).await.unwrap();
+		return;
+	}
+
+	let srim_conf = SrimConfig::from_env();
+	log::info!("S-RIM required return {}%, persistences {:?}", srim_conf.required_return, srim_conf.persistences);
+
 	let today = Local::now().date_naive();
 	let stocks = repo.stocks().list().await.unwrap();
 
@@ -27,36 +90,83 @@ async fn main() {
 
 		let stock_code = stock.code.clone();
 		let stock_name = stock.name.clone();
-		match fetch_data(stock, &repo).await {
+		match fetch_data(stock, args.as_of, args.basis.unwrap_or_default(), &srim_conf, &repo).await {
 			Ok(data) => data_list.push_back(data),
 			Err(err) => log::error!("Failed to fetch data for stock `{}|{}': {}", stock_code, stock_name, err),
 		}
 	}
 
-	create_csv(&data_list).await.unwrap();
+	create_csv(&data_list, &args).await.unwrap();
 }
 
 struct Data {
 	stock: Stock,
+	/// 기준일자
+	date: NaiveDate,
 	price_latest: Option<StockPrice>,
 	price_range: Option<StockPriceRange>,
 	annuals: Vec<FinancialInfo>,
 	quarters: Vec<FinancialInfo>,
+	annual_estimate: Option<FiEstimate>,
+	quarter_estimate: Option<FiEstimate>,
+	ttm: Option<Ttm>,
+	/// 최근 분기의 전년동기대비
+	yoy: Option<GrowthRates>,
+	/// 최근 분기의 직전분기대비
+	qoq: Option<GrowthRates>,
+	/// YoY, QoQ 를 계산한 분기 중 연간 실적에서 계산한 분기가 있는지
+	yoy_derived: bool,
+	qoq_derived: bool,
+	srim: Option<Srim>,
 }
 
-async fn fetch_data(stock: Stock, repo: &Repo) -> Result<Data, Error> {
-	let price_latest = repo.stock_prices().latest(&stock.code).await?;
+/// `as_of` 가 주어지면 그날까지 알려진 실적, 추정실적과 그날의 시세만 사용한다
+async fn fetch_data(stock: Stock, as_of: Option<NaiveDate>, basis: StatementBasis, srim_conf: &SrimConfig, repo: &Repo) -> Result<Data, Error> {
+	let date = as_of.unwrap_or(stock.info_date);
+	let price_latest = match as_of {
+		Some(as_of) => repo.stock_prices().latest_as_of(&stock.code, as_of).await?,
+		None => repo.stock_prices().latest(&stock.code).await?,
+	};
 	let recent_five_years = {
-		let end_date = stock.info_date + Duration::days(1);
+		let end_date = date + Duration::days(1);
 		let start_date = end_date - Duration::days(365 * 5);
 		Range { start: start_date,  end: end_date }
 	};
 	let price_range = repo.stock_prices().range(&stock.code, recent_five_years).await?;
-	let annuals: Vec<_> = repo.fi_annuals().list(&stock.code).await?
-		.into_iter().rev().take(3).rev().collect();
-	let quarters: Vec<_> = repo.fi_quarters().list(&stock.code).await?
-		.into_iter().rev().take(3).rev().collect();
-	Ok(Data { stock, price_latest, price_range, annuals, quarters })
+	let (annuals, quarters, estimates) = match as_of {
+		Some(as_of) => (
+			repo.fi_annuals().list_as_of(&stock.code, as_of).await?,
+			repo.fi_quarters().list_as_of(&stock.code, as_of).await?,
+			repo.fi_estimates().latest_as_of(&stock.code, as_of).await?,
+		),
+		None => (
+			repo.fi_annuals().list(&stock.code).await?,
+			repo.fi_quarters().list(&stock.code).await?,
+			repo.fi_estimates().latest(&stock.code).await?,
+		),
+	};
+	let annuals = analytics::select_basis(annuals, basis);
+	let quarters = analytics::select_basis(quarters, basis);
+	let ttm = analytics::trailing_twelve_months(&quarters);
+	let yoy = analytics::year_over_year(&quarters);
+	let qoq = analytics::quarter_over_quarter(&quarters);
+	let yoy_derived = yoy.as_ref().is_some_and(|rates| compares_derived(&quarters, rates));
+	let qoq_derived = qoq.as_ref().is_some_and(|rates| compares_derived(&quarters, rates));
+	let annuals: Vec<_> = annuals.into_iter().rev().take(3).rev().collect();
+	let quarters: Vec<_> = quarters.into_iter().rev().take(3).rev().collect();
+	let annual_estimate = next_estimate(&estimates, FiTerm::Annual, annuals.last());
+	let quarter_estimate = next_estimate(&estimates, FiTerm::Quarter, quarters.last());
+	let price = price_latest.as_ref().and_then(|p| p.closing);
+	let srim = analytics::srim_from_financials(&annuals, &quarters, annual_estimate.as_ref().map(|e| &e.info), price, srim_conf);
+	Ok(Data { stock, date, price_latest, price_range, annuals, quarters, annual_estimate, quarter_estimate, ttm, yoy, qoq, yoy_derived, qoq_derived, srim })
+}
+
+/// 가장 최근 실적 이후의 첫번째 추정실적
+fn next_estimate(estimates: &[FiEstimate], term: FiTerm, latest_actual: Option<&FinancialInfo>) -> Option<FiEstimate> {
+	estimates.iter()
+		.filter(|e| e.term == term)
+		.find(|e| latest_actual.is_none_or(|fi| e.info.year_month > fi.year_month))
+		.cloned()
 }
 
 #[derive(Default, Serialize)]
@@ -78,96 +188,230 @@ struct Record {
 	price: Option<u32>,
 	/// 시가총액
 	#[serde(rename = "시가총액(억)")]
-	market_cap: Option<u32>,
+	market_cap: Option<i64>,
 	/// 최근5년 최고가
 	#[serde(rename = "최근5년 최고가")]
 	highest_in_recent: Option<u32>,
 	/// 최근5년 최저가
 	#[serde(rename = "최근5년 최저가")]
 	lowest_in_recent: Option<u32>,
+	/// 가장 최근 연간실적의 재무제표 구분 (연결/별도)
+	#[serde(rename = "재무제표")]
+	basis: Option<&'static str>,
 	/// 연간실적 기준년월 1
 	#[serde(rename = "연간실적(Y-3)")]
 	y1_date: Option<YearMonth>,
 	/// 매출액 - 연간실적 기준년월 1
 	#[serde(rename = "매출액(억)")]
-	y1_sales: Option<i32>,
+	y1_sales: Option<i64>,
 	/// 영업이익 - 연간실적 기준년월 1
 	#[serde(rename = "영업이익(억)")]
-	y1_profit: Option<i32>,
+	y1_profit: Option<i64>,
 	/// 시가배당률 - 연간실적 기준년월 1
 	#[serde(rename = "시가배당률(%)")]
-	y1_dividend_yield: Option<f32>,
+	y1_dividend_yield: Option<Amount>,
 	#[serde(rename = "연간실적(Y-2)")]
 	y2_date: Option<YearMonth>,
 	#[serde(rename = "매출액(억)")]
-	y2_sales: Option<i32>,
+	y2_sales: Option<i64>,
 	#[serde(rename = "영업이익(억)")]
-	y2_profit: Option<i32>,
+	y2_profit: Option<i64>,
 	#[serde(rename = "시가배당률(%)")]
-	y2_dividend_yield: Option<f32>,
+	y2_dividend_yield: Option<Amount>,
 	#[serde(rename = "연간실적(Y-1)")]
 	y3_date: Option<YearMonth>,
 	#[serde(rename = "매출액(억)")]
-	y3_sales: Option<i32>,
+	y3_sales: Option<i64>,
+	#[serde(rename = "영업이익(억)")]
+	y3_profit: Option<i64>,
+	#[serde(rename = "시가배당률(%)")]
+	y3_dividend_yield: Option<Amount>,
+	/// 연간 추정실적 기준년월
+	#[serde(rename = "연간추정(E)")]
+	ye_date: Option<YearMonth>,
+	#[serde(rename = "매출액(억)")]
+	ye_sales: Option<i64>,
 	#[serde(rename = "영업이익(억)")]
-	y3_profit: Option<i32>,
+	ye_profit: Option<i64>,
 	#[serde(rename = "시가배당률(%)")]
-	y3_dividend_yield: Option<f32>,
+	ye_dividend_yield: Option<Amount>,
 	/// 분기실적 기준년월 1
 	#[serde(rename = "분기실적(Q-3)")]
 	q1_date: Option<YearMonth>,
 	/// 매출액 - 분기실적 기준년월 1
 	#[serde(rename = "매출액(억)")]
-	q1_sales: Option<i32>,
+	q1_sales: Option<i64>,
 	/// 영업이익 - 분기실적 기준년월 1
 	#[serde(rename = "영업이익(억)")]
-	q1_profit: Option<i32>,
+	q1_profit: Option<i64>,
 	#[serde(rename = "시가배당률(%)")]
-	q1_dividend_yield: Option<f32>,
+	q1_dividend_yield: Option<Amount>,
+	/// 연간 실적에서 계산한 분기이면 `Y`
+	#[serde(rename = "계산분기")]
+	q1_derived: Option<&'static str>,
 	#[serde(rename = "분기실적(Q-2)")]
 	q2_date: Option<YearMonth>,
 	#[serde(rename = "매출액(억)")]
-	q2_sales: Option<i32>,
+	q2_sales: Option<i64>,
 	#[serde(rename = "영업이익(억)")]
-	q2_profit: Option<i32>,
+	q2_profit: Option<i64>,
 	#[serde(rename = "시가배당률(%)")]
-	q2_dividend_yield: Option<f32>,
+	q2_dividend_yield: Option<Amount>,
+	#[serde(rename = "계산분기")]
+	q2_derived: Option<&'static str>,
 	#[serde(rename = "분기실적(Q-1)")]
 	q3_date: Option<YearMonth>,
 	#[serde(rename = "매출액(억)")]
-	q3_sales: Option<i32>,
+	q3_sales: Option<i64>,
 	#[serde(rename = "영업이익(억)")]
-	q3_profit: Option<i32>,
+	q3_profit: Option<i64>,
 	#[serde(rename = "시가배당률(%)")]
-	q3_dividend_yield: Option<f32>,
+	q3_dividend_yield: Option<Amount>,
+	#[serde(rename = "계산분기")]
+	q3_derived: Option<&'static str>,
+	/// 분기 추정실적 기준년월
+	#[serde(rename = "분기추정(E)")]
+	qe_date: Option<YearMonth>,
+	#[serde(rename = "매출액(억)")]
+	qe_sales: Option<i64>,
+	#[serde(rename = "영업이익(억)")]
+	qe_profit: Option<i64>,
+	#[serde(rename = "시가배당률(%)")]
+	qe_dividend_yield: Option<Amount>,
+	/// 최근 4개 분기 합산의 마지막 분기
+	#[serde(rename = "TTM")]
+	ttm_date: Option<YearMonth>,
+	#[serde(rename = "매출액(억)")]
+	ttm_sales: Option<i64>,
+	#[serde(rename = "영업이익(억)")]
+	ttm_profit: Option<i64>,
+	#[serde(rename = "당기순이익(억)")]
+	ttm_net_income: Option<i64>,
+	/// 합산하지 못한 분기들
+	#[serde(rename = "TTM 누락분기")]
+	ttm_missing: Option<String>,
+	/// 연간 실적에서 계산한 분기를 합산했으면 `Y`
+	#[serde(rename = "TTM추정포함")]
+	ttm_derived: Option<&'static str>,
+	/// 최근 분기의 전년동기대비
+	#[serde(rename = "매출액 YoY(%)")]
+	yoy_sales: Option<Growth>,
+	#[serde(rename = "영업이익 YoY(%)")]
+	yoy_profit: Option<Growth>,
+	#[serde(rename = "당기순이익 YoY(%)")]
+	yoy_net_income: Option<Growth>,
+	/// 비교한 두 분기 중 연간 실적에서 계산한 분기가 있으면 `Y`
+	#[serde(rename = "YoY추정포함")]
+	yoy_derived: Option<&'static str>,
+	/// 최근 분기의 직전분기대비
+	#[serde(rename = "매출액 QoQ(%)")]
+	qoq_sales: Option<Growth>,
+	#[serde(rename = "영업이익 QoQ(%)")]
+	qoq_profit: Option<Growth>,
+	#[serde(rename = "당기순이익 QoQ(%)")]
+	qoq_net_income: Option<Growth>,
+	#[serde(rename = "QoQ추정포함")]
+	qoq_derived: Option<&'static str>,
+	/// S-RIM 에 사용한 ROE
+	#[serde(rename = "S-RIM ROE(%)")]
+	srim_roe: Option<f32>,
+	/// `SrimConfig::persistences` 순서의 적정주가와 할인율
+	#[serde(rename = "S-RIM 적정가(1)")]
+	srim1_value: Option<i64>,
+	#[serde(rename = "할인율(%)")]
+	srim1_discount: Option<f32>,
+	#[serde(rename = "S-RIM 적정가(2)")]
+	srim2_value: Option<i64>,
+	#[serde(rename = "할인율(%)")]
+	srim2_discount: Option<f32>,
+	#[serde(rename = "S-RIM 적정가(3)")]
+	srim3_value: Option<i64>,
+	#[serde(rename = "할인율(%)")]
+	srim3_discount: Option<f32>,
+}
+
+#[derive(Default, Serialize)]
+struct ValuationRecord {
+	/// 손익 기준 (TTM/ANNUAL)
+	#[serde(rename = "지표기준")]
+	basis: Option<&'static str>,
+	#[serde(rename = "PER")]
+	per: Ratio,
+	#[serde(rename = "PBR")]
+	pbr: Ratio,
+	#[serde(rename = "PSR")]
+	psr: Ratio,
+	#[serde(rename = "POR")]
+	por: Ratio,
+	#[serde(rename = "이익수익률(%)")]
+	earnings_yield: Ratio,
+	#[serde(rename = "배당수익률(%)")]
+	dividend_yield: Ratio,
+}
+
+impl From<&Data> for ValuationRecord {
+	fn from(data: &Data) -> Self {
+		let valuation = analytics::valuate(&ValuationInput {
+			price: data.price_latest.as_ref().and_then(|p| p.closing),
+			shares: data.stock.list_shares,
+			ttm: data.ttm.as_ref(),
+			annual: data.annuals.last(),
+		});
+		Self {
+			basis: valuation.basis.map(|b| b.as_str()),
+			per: valuation.per,
+			pbr: valuation.pbr,
+			psr: valuation.psr,
+			por: valuation.por,
+			earnings_yield: valuation.earnings_yield,
+			dividend_yield: valuation.dividend_yield,
+		}
+	}
 }
 
-async fn create_csv(data_list: &LinkedList<Data>) -> Result<(), Error> {
-	let file = File::create("종목별실적데이터.csv")?;
+async fn create_csv(data_list: &LinkedList<Data>, args: &Args) -> Result<(), Error> {
+	let file_name = match args.as_of {
+		Some(as_of) => format!("종목별실적데이터_{as_of}.csv"),
+		None => "종목별실적데이터.csv".to_string(),
+	};
+	let file = File::create(file_name)?;
 	// let mut writer = Writer::from_writer(file);
 	let mut writer = WriterBuilder::new()
 		.quote_style(QuoteStyle::NonNumeric) // Set the quoting style
+		.has_headers(false)
 		.from_writer(file);
 		// .from_writer(io::stdout());
 	log::info!("Writing {} data in CSV...", data_list.len());
 
+	let unit = args.amount_unit();
+	let headers = if args.valuation {
+		headers(&(Record::default(), ValuationRecord::default()), unit)?
+	} else {
+		headers(&Record::default(), unit)?
+	};
+	writer.write_record(&headers)?;
+
 	for data in data_list.iter() {
 		let mut rec = Record::default();
 		rec.code = data.stock.code.clone();
 		rec.name = data.stock.name.clone();
 		rec.market = data.stock.market.as_str();
-		rec.date = data.stock.info_date;
+		rec.date = data.date;
 		rec.price = data.price_latest.as_ref().and_then(|p| p.closing);
-		rec.market_cap = rec.price.zip(data.stock.list_shares).map(|(price, shares)| (price as f64 * shares as f64 / 100000000 as f64).round() as u32);
+		rec.market_cap = rec.price.zip(data.stock.list_shares)
+			.and_then(|(price, shares)| Unit::Won.convert(Amount::from(price as u64 * shares), unit))
+			.and_then(|v| v.round().to_i64());
 		rec.highest_in_recent = data.price_range.as_ref().and_then(|r| r.highest);
 		rec.lowest_in_recent = data.price_range.as_ref().and_then(|r| r.lowest);
 
+		rec.basis = data.annuals.last().map(|fi| fi.basis.label());
+
 		let i = data.annuals.len() as i32 - 1;
 		if i >= 0 {
 			let annual = &data.annuals[i as usize];
 			rec.y3_date = Some(annual.year_month);
-			rec.y3_sales = annual.sales.map(|v| v as i32);
-			rec.y3_profit = annual.profit.map(|v| v as i32);
+			rec.y3_sales = amount(annual.sales, unit);
+			rec.y3_profit = amount(annual.profit, unit);
 			rec.y3_dividend_yield = annual.dividend_yield;
 		}
 
@@ -175,8 +419,8 @@ async fn create_csv(data_list: &LinkedList<Data>) -> Result<(), Error> {
 		if i >= 0 {
 			let annual = &data.annuals[i as usize];
 			rec.y2_date = Some(annual.year_month);
-			rec.y2_sales = annual.sales.map(|v| v as i32);
-			rec.y2_profit = annual.profit.map(|v| v as i32);
+			rec.y2_sales = amount(annual.sales, unit);
+			rec.y2_profit = amount(annual.profit, unit);
 			rec.y2_dividend_yield = annual.dividend_yield;
 		}
 
@@ -184,8 +428,8 @@ async fn create_csv(data_list: &LinkedList<Data>) -> Result<(), Error> {
 		if i >= 0 {
 			let annual = &data.annuals[i as usize];
 			rec.y1_date = Some(annual.year_month);
-			rec.y1_sales = annual.sales.map(|v| v as i32);
-			rec.y1_profit = annual.profit.map(|v| v as i32);
+			rec.y1_sales = amount(annual.sales, unit);
+			rec.y1_profit = amount(annual.profit, unit);
 			rec.y1_dividend_yield = annual.dividend_yield;
 		}
 
@@ -193,32 +437,119 @@ async fn create_csv(data_list: &LinkedList<Data>) -> Result<(), Error> {
 		if i >= 0 {
 			let quarter = &data.quarters[i as usize];
 			rec.q3_date = Some(quarter.year_month);
-			rec.q3_sales = quarter.sales.map(|v| v as i32);
-			rec.q3_profit = quarter.profit.map(|v| v as i32);
+			rec.q3_sales = amount(quarter.sales, unit);
+			rec.q3_profit = amount(quarter.profit, unit);
 			rec.q3_dividend_yield = quarter.dividend_yield;
+			rec.q3_derived = derived_flag(quarter.derived);
 		}
 
 		let i = data.quarters.len() as i32 - 2;
 		if i >= 0 {
 			let quarter = &data.quarters[i as usize];
 			rec.q2_date = Some(quarter.year_month);
-			rec.q2_sales = quarter.sales.map(|v| v as i32);
-			rec.q2_profit = quarter.profit.map(|v| v as i32);
+			rec.q2_sales = amount(quarter.sales, unit);
+			rec.q2_profit = amount(quarter.profit, unit);
 			rec.q2_dividend_yield = quarter.dividend_yield;
+			rec.q2_derived = derived_flag(quarter.derived);
 		}
 
 		let i = data.quarters.len() as i32 - 3;
 		if i >= 0 {
 			let quarter = &data.quarters[i as usize];
 			rec.q1_date = Some(quarter.year_month);
-			rec.q1_sales = quarter.sales.map(|v| v as i32);
-			rec.q1_profit = quarter.profit.map(|v| v as i32);
+			rec.q1_sales = amount(quarter.sales, unit);
+			rec.q1_profit = amount(quarter.profit, unit);
 			rec.q1_dividend_yield = quarter.dividend_yield;
+			rec.q1_derived = derived_flag(quarter.derived);
+		}
+
+		if let Some(estimate) = data.annual_estimate.as_ref() {
+			rec.ye_date = Some(estimate.info.year_month);
+			rec.ye_sales = amount(estimate.info.sales, unit);
+			rec.ye_profit = amount(estimate.info.profit, unit);
+			rec.ye_dividend_yield = estimate.info.dividend_yield;
+		}
+
+		if let Some(estimate) = data.quarter_estimate.as_ref() {
+			rec.qe_date = Some(estimate.info.year_month);
+			rec.qe_sales = amount(estimate.info.sales, unit);
+			rec.qe_profit = amount(estimate.info.profit, unit);
+			rec.qe_dividend_yield = estimate.info.dividend_yield;
+		}
+
+		if let Some(ttm) = data.ttm.as_ref() {
+			rec.ttm_date = Some(ttm.year_month);
+			rec.ttm_sales = amount(ttm.sales, unit);
+			rec.ttm_profit = amount(ttm.profit, unit);
+			rec.ttm_net_income = amount(ttm.net_income, unit);
+			rec.ttm_derived = derived_flag(ttm.includes_derived);
+			if !ttm.is_complete() {
+				rec.ttm_missing = Some(ttm.missing.iter().map(|ym| ym.to_string()).collect::<Vec<_>>().join(" "));
+			}
 		}
 
-		writer.serialize(rec)?;
+		if let Some(yoy) = data.yoy.as_ref() {
+			rec.yoy_sales = Some(yoy.sales);
+			rec.yoy_profit = Some(yoy.profit);
+			rec.yoy_net_income = Some(yoy.net_income);
+			rec.yoy_derived = derived_flag(data.yoy_derived);
+		}
+
+		if let Some(qoq) = data.qoq.as_ref() {
+			rec.qoq_sales = Some(qoq.sales);
+			rec.qoq_profit = Some(qoq.profit);
+			rec.qoq_net_income = Some(qoq.net_income);
+			rec.qoq_derived = derived_flag(data.qoq_derived);
+		}
+
+		if let Some(srim) = data.srim.as_ref() {
+			rec.srim_roe = Some(((srim.roe * 100.0).round() / 100.0) as f32);
+			let values = srim.values.iter()
+				.map(|v| (Some(v.value.round() as i64), v.discount.map(|d| ((d * 10.0).round() / 10.0) as f32)))
+				.chain(std::iter::repeat((None, None)));
+			let slots = [
+				(&mut rec.srim1_value, &mut rec.srim1_discount),
+				(&mut rec.srim2_value, &mut rec.srim2_discount),
+				(&mut rec.srim3_value, &mut rec.srim3_discount),
+			];
+			for ((value_slot, discount_slot), (value, discount)) in slots.into_iter().zip(values) {
+				*value_slot = value;
+				*discount_slot = discount;
+			}
+		}
+
+		if args.valuation {
+			writer.serialize((rec, ValuationRecord::from(data)))?;
+		} else {
+			writer.serialize(rec)?;
+		}
 	}
 
 	writer.flush()?;
 	Ok(())
 }
+
+/// 연간 실적에서 계산한 값이 들어갔음을 표시하는 컬럼 값
+fn derived_flag(derived: bool) -> Option<&'static str> {
+	derived.then_some("Y")
+}
+
+/// 증감률을 계산한 두 분기 중 연간 실적에서 계산한 분기가 있는지
+fn compares_derived(quarters: &[FinancialInfo], rates: &GrowthRates) -> bool {
+	quarters.iter()
+		.filter(|q| q.year_month == rates.year_month || q.year_month == rates.base_year_month)
+		.any(|q| q.derived)
+}
+
+/// 억원 단위로 저장된 금액을 내보낼 단위의 정수로
+fn amount(value: Option<Amount>, unit: Unit) -> Option<i64> {
+	value.and_then(|v| FinancialInfo::AMOUNT_UNIT.convert(v, unit))
+		.and_then(|v| v.round().to_i64())
+}
+
+/// 레코드의 serde 필드 이름에서 금액 단위 `(억)` 을 내보낼 단위로 바꾼 헤더
+fn headers<T: Serialize>(row: &T, unit: Unit) -> Result<Vec<String>, Error> {
+	let names = header::field_names(row).map_err(|err| err.to_string())?;
+	let label = format!("({})", unit.label());
+	Ok(names.into_iter().map(|name| name.replace("(억)", &label)).collect())
+}
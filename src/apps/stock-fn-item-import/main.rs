@@ -0,0 +1,87 @@
+use chrono::{Local, NaiveDate};
+
+use crate::{data_source::{load_etf_master, load_stock_master}, entities::{RepoTx, Repository}, item_master::ItemMaster, repository::DatabaseConfig, types::{Error, Market}};
+
+use stock_fn_scraper::logger;
+use stock_fn_scraper::entities;
+use stock_fn_scraper::repository;
+use stock_fn_scraper::data_source;
+use stock_fn_scraper::item_master;
+use stock_fn_scraper::types;
+
+const USAGE: &str = "Usage: stock-fn-item-import [--date YYYY-MM-DD] [--stocks 전종목기본정보.csv] [--etfs ETF기본정보.csv]";
+
+struct Args {
+	info_date: NaiveDate,
+	stocks_file: Option<String>,
+	etfs_file: Option<String>,
+}
+
+fn parse_args() -> Result<Args, Error> {
+	let mut args = Args {
+		info_date: Local::now().date_naive(),
+		stocks_file: None,
+		etfs_file: None,
+	};
+
+	let mut iter = std::env::args().skip(1);
+	while let Some(arg) = iter.next() {
+		let mut value = || iter.next().ok_or_else(|| Error::from(format!("Missing value for `{arg}`")));
+		match arg.as_str() {
+			"--date" => args.info_date = NaiveDate::parse_from_str(&value()?, "%Y-%m-%d")?,
+			"--stocks" => args.stocks_file = Some(value()?),
+			"--etfs" => args.etfs_file = Some(value()?),
+			_ => return Err(format!("Unknown argument `{arg}`").into()),
+		}
+	}
+
+	if args.stocks_file.is_none() && args.etfs_file.is_none() {
+		return Err("Nothing to import".into());
+	}
+	Ok(args)
+}
+
+#[tokio::main]
+async fn main() {
+	dotenvy::dotenv().ok();
+	logger::prepare();
+
+	let args = parse_args().unwrap_or_else(|err| {
+		eprintln!("{err}\n{USAGE}");
+		std::process::exit(2);
+	});
+
+	let mut master = ItemMaster::new(args.info_date);
+	if let Some(path) = args.stocks_file.as_ref() {
+		let items = load_stock_master(path, args.info_date).unwrap();
+		log::info!("{} stocks loaded from `{}`", items.len(), path);
+		master.extend(&[Market::KOSPI, Market::KOSDAQ], items);
+	}
+	if let Some(path) = args.etfs_file.as_ref() {
+		let items = load_etf_master(path, args.info_date).unwrap();
+		log::info!("{} ETFs loaded from `{}`", items.len(), path);
+		master.extend(&[Market::ETF], items);
+	}
+
+	let db_conf = DatabaseConfig::from_env();
+	let repo = repository::create(&db_conf).await;
+
+	let tx = repo.transaction().await.unwrap();
+	let summary = match master.save(tx.as_ref()).await {
+		Ok(summary) => summary,
+		Err(err) => {
+			log::error!("Failed to import item master: {}", err);
+			tx.rollback().await.unwrap();
+			std::process::exit(1);
+		}
+	};
+	tx.commit().await.unwrap();
+
+	for change in summary.listed.iter() {
+		log::info!("Listed: `{}|{}` ({})", change.code, change.name, change.market.as_str());
+	}
+	for change in summary.delisted.iter() {
+		log::info!("Delisted: `{}|{}` ({})", change.code, change.name, change.market.as_str());
+	}
+	log::info!("{} items imported. ({} listed, {} delisted)", summary.upserted, summary.listed.len(), summary.delisted.len());
+}
@@ -0,0 +1,165 @@
+use std::{collections::BTreeMap, sync::Arc};
+
+use chrono::{Local, NaiveDate};
+
+use crate::{data_source::{ArchiveConfig, HttpClient, HttpConfig, NaverDataSource, ResponseArchive}, entities::{EntityDao, Stock, StockPrice}, repository::{DatabaseConfig, Repo}, types::Error};
+
+use stock_fn_scraper::logger;
+use stock_fn_scraper::entities;
+use stock_fn_scraper::repository;
+use stock_fn_scraper::data_source;
+use stock_fn_scraper::types;
+
+/// 저장된 시세가 없는 종목은 상장 이후 전체 일봉을 받는다
+const FULL_HISTORY_COUNT: usize = 10000;
+
+const USAGE: &str = "Usage: stock-fn-price-scrap [--from-archive]";
+
+/// `--from-archive`: 네트워크 대신 보관된 일봉 데이터를 다시 파싱한다
+fn parse_args() -> Result<bool, Error> {
+	let mut from_archive = false;
+	for arg in std::env::args().skip(1) {
+		match arg.as_str() {
+			"--from-archive" => from_archive = true,
+			_ => return Err(format!("Unknown argument `{arg}`").into()),
+		}
+	}
+	Ok(from_archive)
+}
+
+#[tokio::main]
+async fn main() {
+	dotenvy::dotenv().ok();
+	logger::prepare();
+
+	let from_archive = parse_args().unwrap_or_else(|err| {
+		eprintln!("{err}\n{USAGE}");
+		std::process::exit(2);
+	});
+
+	let db_conf = DatabaseConfig::from_env();
+	let repo = repository::create(&db_conf).await;
+
+	let archive_dir = ArchiveConfig::from_env().dir;
+	if from_archive {
+		let Some(dir) = archive_dir.as_ref() else {
+			eprintln!("ARCHIVE_DIR must be set to use --from-archive");
+			std::process::exit(2);
+		};
+		let archive = ResponseArchive::open(dir).unwrap();
+		reparse_archive(&archive, &repo).await;
+		return;
+	}
+
+	let http = HttpClient::new(&HttpConfig::from_env()).unwrap();
+	let naver = NaverDataSource::new(Arc::new(http));
+	let naver = match archive_dir {
+		Some(dir) => naver.with_archive(Arc::new(ResponseArchive::open(dir).unwrap())),
+		None => naver,
+	};
+
+	let today = Local::now().date_naive();
+	let stocks = repo.stocks().list().await.unwrap();
+
+	let stocks_len = stocks.len();
+	let mut fetched_stocks = 0;
+	let mut skipped_stocks = 0;
+	let mut error_stocks = 0;
+
+	for stock in stocks.iter() {
+		if today - stock.info_date > chrono::Duration::days(10) {
+			log::info!("Stock `{}|{}` Outdated and skipped (date:{})", stock.code, stock.name, stock.info_date);
+			skipped_stocks += 1;
+
+		} else {
+			match scrap_prices(stock, &naver, &repo).await {
+				Ok(0) => {
+					log::info!("Prices of Stock `{}|{}` are up to date", stock.code, stock.name);
+					skipped_stocks += 1;
+				}
+				Ok(count) => {
+					log::info!("Prices of Stock `{}|{}` fetched. ({} days)", stock.code, stock.name, count);
+					fetched_stocks += 1;
+				},
+				Err(err) => {
+					log::error!("Error: {:?} - Stock: `{}|{}`", err, stock.code, stock.name);
+					error_stocks += 1;
+				}
+			}
+		}
+
+		log::info!("{fetched_stocks}/{stocks_len} fetched. ({skipped_stocks} skipped, {error_stocks} errors)");
+	}
+}
+
+/// 마지막으로 저장된 일자 이후의 시세만 받아서 저장하고, 저장된 일수를 돌려준다
+async fn scrap_prices(stock: &Stock, naver: &NaverDataSource, repo: &Repo) -> Result<usize, Error> {
+	let today = Local::now().date_naive();
+	let latest = repo.stock_prices().latest(&stock.code).await?;
+
+	let count = match latest.as_ref() {
+		Some(latest) if latest.ord_date >= today => return Ok(0),
+		// 전일대비 계산을 위해 마지막 저장일자까지 포함해서 받는다
+		Some(latest) => (today - latest.ord_date).num_days() as usize + 1,
+		None => FULL_HISTORY_COUNT,
+	};
+
+	let mut prices = naver.query_daily_prices(&stock.code, count).await?;
+	if let Some(latest) = latest.as_ref() {
+		prices.retain(|p| p.ord_date > latest.ord_date);
+	}
+
+	repo.stock_prices().upsert(&prices).await?;
+	Ok(prices.len())
+}
+
+/// 종목마다 보관된 일봉 데이터를 다시 파싱해서 저장한다
+async fn reparse_archive(archive: &ResponseArchive, repo: &Repo) {
+	let stocks = repo.stocks().list().await.unwrap();
+
+	let stocks_len = stocks.len();
+	let mut parsed_stocks = 0;
+	let mut skipped_stocks = 0;
+	let mut error_stocks = 0;
+
+	for stock in stocks.iter() {
+		match reparse_prices(stock, archive, repo).await {
+			Ok(0) => {
+				log::info!("No archived prices of Stock `{}|{}`", stock.code, stock.name);
+				skipped_stocks += 1;
+			}
+			Ok(count) => {
+				log::info!("Prices of Stock `{}|{}` re-parsed. ({} days)", stock.code, stock.name, count);
+				parsed_stocks += 1;
+			}
+			Err(err) => {
+				log::error!("Error: {:?} - Stock: `{}|{}`", err, stock.code, stock.name);
+				error_stocks += 1;
+			}
+		}
+
+		log::info!("{parsed_stocks}/{stocks_len} re-parsed. ({skipped_stocks} skipped, {error_stocks} errors)");
+	}
+}
+
+/// 보관된 응답을 가져온 순서대로 합쳐서 저장하고, 저장된 일수를 돌려준다
+async fn reparse_prices(stock: &Stock, archive: &ResponseArchive, repo: &Repo) -> Result<usize, Error> {
+	let entries = archive.list(NaverDataSource::CHART_ARCHIVE, &stock.code).await?;
+
+	let mut prices = BTreeMap::<NaiveDate, StockPrice>::new();
+	for entry in entries.iter() {
+		let parsed = NaverDataSource::parse_archived_prices(archive, entry).await
+			.map_err(|e| format!("{} (archived at {}, {})", e, entry.fetched_at, entry.hash))?;
+		for mut price in parsed {
+			// 증분 조회의 첫 일자는 전일대비를 알 수 없으므로 앞선 응답의 값을 쓴다
+			if let (None, Some(prev)) = (price.diff, prices.get(&price.ord_date)) {
+				price.diff = prev.diff;
+			}
+			prices.insert(price.ord_date, price);
+		}
+	}
+
+	let prices: Vec<StockPrice> = prices.into_values().collect();
+	repo.stock_prices().upsert(&prices).await?;
+	Ok(prices.len())
+}
@@ -1,23 +1,82 @@
+use std::{collections::HashSet, sync::Arc};
+
 use chrono::Local;
 
-use crate::{data_source::{query_stock_financials}, entities::EntityDao, repository::DatabaseConfig};
+use crate::{data_source::{DataSourceConfig, FinancialDataSource, NaverDataSource, ResponseArchive, WisereportDataSource}, entities::{EntityDao, ScrapeItemStatus, ScrapeRun, ScrapeRunItem, Stock}, repository::{DatabaseConfig, Repo}, types::Error};
 
 use stock_fn_scraper::logger;
 use stock_fn_scraper::entities;
 use stock_fn_scraper::repository;
 use stock_fn_scraper::data_source;
+use stock_fn_scraper::types;
+
+use pipeline::PipelineConfig;
 
+mod pipeline;
+
+const USAGE: &str = "Usage: stock-fn-scrap [--resume] [--backfill] | --from-archive";
+
+#[derive(Default)]
+struct Args {
+	/// 네트워크 대신 보관된 기업실적분석, wisereport 재무요약 페이지를 다시 파싱한다. 일봉은 `stock-fn-price-scrap --from-archive`
+	from_archive: bool,
+	/// 마지막 미완료 실행에서 처리하지 못한 종목만 다시 조회한다
+	resume: bool,
+	/// wisereport 재무요약으로 과거 실적을 채운다. 이미 저장된 값은 바꾸지 않는다
+	backfill: bool,
+}
+
+fn parse_args() -> Result<Args, Error> {
+	let mut args = Args::default();
+	for arg in std::env::args().skip(1) {
+		match arg.as_str() {
+			"--from-archive" => args.from_archive = true,
+			"--resume" => args.resume = true,
+			"--backfill" => args.backfill = true,
+			_ => return Err(format!("Unknown argument `{arg}`").into()),
+		}
+	}
+	if args.from_archive && (args.resume || args.backfill) {
+		return Err("`--resume` and `--backfill` can't be used with `--from-archive`".into());
+	}
+	Ok(args)
+}
 
 #[tokio::main]
 async fn main() {
 	dotenvy::dotenv().ok();
 	logger::prepare();
 
+	let args = parse_args().unwrap_or_else(|err| {
+		eprintln!("{err}\n{USAGE}");
+		std::process::exit(2);
+	});
+
 	let db_conf = DatabaseConfig::from_env();
 	let repo = repository::create(&db_conf).await;
 
-	let today = Local::now().date_naive();
-	let stocks = repo.stocks().list().await.unwrap();
+	let mut source_conf = DataSourceConfig::from_env();
+	if args.from_archive {
+		let Some(dir) = source_conf.archive.dir.as_ref() else {
+			eprintln!("ARCHIVE_DIR must be set to use --from-archive");
+			std::process::exit(2);
+		};
+		let archive = ResponseArchive::open(dir).unwrap();
+		reparse_archive(&archive, &repo).await;
+		return;
+	}
+
+	if args.backfill {
+		source_conf.source = WisereportDataSource::NAME.to_string();
+	}
+	let fiscal_months = repo.fiscal_months().list().await.unwrap();
+	let source: Arc<dyn FinancialDataSource> = Arc::from(data_source::create(&source_conf, &fiscal_months).unwrap());
+	let pipeline_conf = PipelineConfig::from_env();
+	log::info!("Scraping with {} fetch workers, {} save workers", pipeline_conf.fetch_workers, pipeline_conf.save_workers);
+
+	let (run, done_codes) = prepare_run(source.name(), args.resume, &repo).await.unwrap();
+	let mut stocks = repo.stocks().list().await.unwrap();
+	stocks.retain(|stock| !done_codes.contains(&stock.code));
 
 	let stocks_len = stocks.len();
 	let mut fetched_stocks = 0;
@@ -25,26 +84,102 @@ async fn main() {
 	let mut error_stocks = 0;
 	let now = Local::now();
 
-	for stock in stocks.iter() {
-		if today - stock.info_date > chrono::Duration::days(10) {
-			log::info!("Stock `{}|{}` Outdated and skipped (date:{})", stock.code, stock.name, stock.info_date);
-			skipped_stocks += 1;
-
-		} else {
-			match query_stock_financials(&stock.code).await {
-				Ok(financials) => {
-					log::info!("Financials of Stock `{}|{}` fetched. ({} annuals, {} quarters)", stock.code, stock.name, financials.annuals.list.len(), financials.quarters.list.len());
-					financials.save(&repo).await.unwrap();
-					fetched_stocks += 1;
-				},
-				Err(err) => {
-					log::error!("Error: {:?} - Stock: `{}|{}`", err, stock.code, stock.name);
-					error_stocks += 1;
-				}
-			}
+	let mut outcomes = pipeline::run(stocks, source, repo.clone(), &pipeline_conf);
+	while let Some(outcome) = outcomes.recv().await {
+		match outcome.status {
+			ScrapeItemStatus::Fetched => fetched_stocks += 1,
+			ScrapeItemStatus::Skipped => skipped_stocks += 1,
+			ScrapeItemStatus::Errored => error_stocks += 1,
+		}
+		let item = ScrapeRunItem {
+			run_id: run.id,
+			stock_code: outcome.stock_code,
+			status: outcome.status,
+			message: outcome.message,
+			updated_at: Local::now().naive_local(),
+		};
+		if let Err(err) = repo.scrape_run_items().upsert(&item).await {
+			log::error!("Failed to record scrape result of Stock `{}`: {}", item.stock_code, err);
 		}
 
 		let delay = ((Local::now() - now).num_milliseconds() as f32) / 100 as f32;
 		log::info!("{fetched_stocks}/{stocks_len} fetched. ({skipped_stocks} skipped, {error_stocks} errors) - {delay:.1} delayed");
 	}
+
+	if error_stocks == 0 {
+		repo.scrape_runs().finish(run.id, Local::now().naive_local()).await.unwrap();
+		log::info!("Scrape run #{} finished", run.id);
+	} else {
+		log::info!("Scrape run #{} left unfinished with {} errors. Run with `--resume` to retry them", run.id, error_stocks);
+	}
+}
+
+/// 새 실행을 기록하거나, 이어서 실행할 경우 마지막 미완료 실행과 이미 처리된 종목코드를 돌려준다
+async fn prepare_run(source: &str, resume: bool, repo: &Repo) -> Result<(ScrapeRun, HashSet<String>), Error> {
+	if resume {
+		if let Some(run) = repo.scrape_runs().last_unfinished(source).await? {
+			let done_codes: HashSet<String> = repo.scrape_run_items().list(run.id).await?
+				.into_iter()
+				.filter(|item| item.status.is_done())
+				.map(|item| item.stock_code)
+				.collect();
+			log::info!("Resuming scrape run #{} started at {} ({} stocks already done)", run.id, run.started_at, done_codes.len());
+			return Ok((run, done_codes));
+		}
+		log::info!("No unfinished scrape run of `{source}` to resume. Starting a new run");
+	}
+
+	let run = repo.scrape_runs().insert(source, Local::now().naive_local()).await?;
+	log::info!("Scrape run #{} started", run.id);
+	Ok((run, HashSet::new()))
+}
+
+/// 종목마다 보관된 페이지를 가져온 순서대로 다시 파싱해서 저장한다
+async fn reparse_archive(archive: &ResponseArchive, repo: &Repo) {
+	let stocks = repo.stocks().list().await.unwrap();
+
+	let stocks_len = stocks.len();
+	let mut parsed_stocks = 0;
+	let mut skipped_stocks = 0;
+	let mut error_stocks = 0;
+
+	for stock in stocks.iter() {
+		match reparse_stock(stock, archive, repo).await {
+			Ok(0) => {
+				log::info!("No archived pages of Stock `{}|{}`", stock.code, stock.name);
+				skipped_stocks += 1;
+			}
+			Ok(count) => {
+				log::info!("Financials of Stock `{}|{}` re-parsed. ({} pages)", stock.code, stock.name, count);
+				parsed_stocks += 1;
+			}
+			Err(err) => {
+				log::error!("Error: {:?} - Stock: `{}|{}`", err, stock.code, stock.name);
+				error_stocks += 1;
+			}
+		}
+
+		log::info!("{parsed_stocks}/{stocks_len} re-parsed. ({skipped_stocks} skipped, {error_stocks} errors)");
+	}
+}
+
+/// 기업실적분석 페이지와 wisereport 재무요약 페이지를 소스에 상관없이 가져온 순서대로 다시 파싱한다
+async fn reparse_stock(stock: &Stock, archive: &ResponseArchive, repo: &Repo) -> Result<usize, Error> {
+	let mut entries = archive.list(NaverDataSource::NAME, &stock.code).await?;
+	for source in WisereportDataSource::SUMMARY_ARCHIVES {
+		entries.extend(archive.list(source, &stock.code).await?);
+	}
+	entries.sort_by_key(|entry| entry.fetched_at);
+
+	for entry in entries.iter() {
+		let financials = if entry.source == NaverDataSource::NAME {
+			NaverDataSource::parse_archived(archive, entry).await
+		} else {
+			WisereportDataSource::parse_archived(archive, entry).await
+		};
+		let financials = financials
+			.map_err(|e| format!("{} (archived {} at {}, {})", e, entry.source, entry.fetched_at, entry.hash))?;
+		financials.save(repo).await?;
+	}
+	Ok(entries.len())
 }
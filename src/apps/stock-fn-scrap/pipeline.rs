@@ -0,0 +1,134 @@
+use std::{env, sync::{Arc, Mutex}};
+
+use chrono::Local;
+use stock_fn_scraper::{data_source::FinancialDataSource, entities::{ScrapeItemStatus, Stock}, fi_registry::Financials, repository::Repo};
+use tokio::sync::mpsc::{self, UnboundedReceiver, UnboundedSender};
+
+#[derive(Debug, Clone)]
+pub struct PipelineConfig {
+	/// 동시에 조회하는 종목 수. 실제 요청 간격은 공용 HTTP 클라이언트의 호스트별 제한을 따른다
+	pub fetch_workers: usize,
+	/// 동시에 저장하는 종목 수
+	pub save_workers: usize,
+	/// 저장을 기다리는 종목 수의 상한. 가득 차면 조회가 멈춘다
+	pub queue_size: usize,
+}
+
+impl PipelineConfig {
+	pub fn from_env() -> Self {
+		let number = |name: &str, default: usize| env::var(name)
+			.ok()
+			.and_then(|s| s.parse::<usize>().ok())
+			.filter(|n| *n > 0)
+			.unwrap_or(default);
+		Self {
+			fetch_workers: number("SCRAP_FETCH_WORKERS", 4),
+			save_workers: number("SCRAP_SAVE_WORKERS", 2),
+			queue_size: number("SCRAP_QUEUE_SIZE", 16),
+		}
+	}
+}
+
+/// 종목 하나의 처리 결과
+pub struct Outcome {
+	pub stock_code: String,
+	pub status: ScrapeItemStatus,
+	/// 오류 메시지
+	pub message: Option<String>,
+}
+
+impl Outcome {
+	fn new(stock: &Stock, status: ScrapeItemStatus, message: Option<String>) -> Self {
+		Self {
+			stock_code: stock.code.clone(),
+			status,
+			message,
+		}
+	}
+}
+
+/// 조회와 저장을 별도의 작업자들로 나눠 실행하고, 종목마다 한 번씩 처리 결과를 보낸다.
+///
+/// 모든 종목의 결과가 전달되면 채널이 닫힌다.
+pub fn run(stocks: Vec<Stock>, source: Arc<dyn FinancialDataSource>, repo: Repo, conf: &PipelineConfig) -> UnboundedReceiver<Outcome> {
+	let (outcome_tx, outcome_rx) = mpsc::unbounded_channel::<Outcome>();
+	let (save_tx, save_rx) = mpsc::channel::<(Stock, Financials)>(conf.queue_size);
+
+	let stocks = Arc::new(Mutex::new(stocks.into_iter()));
+	for _ in 0..conf.fetch_workers {
+		tokio::spawn(fetch_worker(stocks.clone(), source.clone(), save_tx.clone(), outcome_tx.clone()));
+	}
+	drop(save_tx);
+
+	let save_rx = Arc::new(tokio::sync::Mutex::new(save_rx));
+	for _ in 0..conf.save_workers {
+		tokio::spawn(save_worker(save_rx.clone(), repo.clone(), outcome_tx.clone()));
+	}
+
+	outcome_rx
+}
+
+async fn fetch_worker(
+	stocks: Arc<Mutex<std::vec::IntoIter<Stock>>>,
+	source: Arc<dyn FinancialDataSource>,
+	save_tx: mpsc::Sender<(Stock, Financials)>,
+	outcome_tx: UnboundedSender<Outcome>,
+) {
+	let today = Local::now().date_naive();
+	while let Some(stock) = next_stock(&stocks) {
+		if today - stock.info_date > chrono::Duration::days(10) {
+			log::info!("Stock `{}|{}` Outdated and skipped (date:{})", stock.code, stock.name, stock.info_date);
+			outcome_tx.send(Outcome::new(&stock, ScrapeItemStatus::Skipped, None)).ok();
+			continue;
+		}
+
+		match source.query_stock_financials(&stock.code).await {
+			Ok(financials) => {
+				log::info!("Financials of Stock `{}|{}` fetched. ({} annuals, {} quarters)", stock.code, stock.name, financials.annuals.list.len(), financials.quarters.list.len());
+				// 저장 대기열이 가득 차면 여기서 기다린다
+				if let Err(mpsc::error::SendError((stock, _))) = save_tx.send((stock, financials)).await {
+					// 저장 작업자가 모두 끝났으면 이 종목과 남은 종목은 저장할 수 없다
+					log::error!("Save queue closed - Stock: `{}|{}`", stock.code, stock.name);
+					outcome_tx.send(Outcome::new(&stock, ScrapeItemStatus::Errored, Some(SAVE_QUEUE_CLOSED.to_string()))).ok();
+					while let Some(stock) = next_stock(&stocks) {
+						outcome_tx.send(Outcome::new(&stock, ScrapeItemStatus::Errored, Some(SAVE_QUEUE_CLOSED.to_string()))).ok();
+					}
+					break;
+				}
+			},
+			Err(err) => {
+				log::error!("Error: {:?} - Stock: `{}|{}`", err, stock.code, stock.name);
+				outcome_tx.send(Outcome::new(&stock, ScrapeItemStatus::Errored, Some(err.to_string()))).ok();
+			}
+		}
+	}
+}
+
+const SAVE_QUEUE_CLOSED: &str = "save queue closed";
+
+fn next_stock(stocks: &Mutex<std::vec::IntoIter<Stock>>) -> Option<Stock> {
+	stocks.lock().unwrap().next()
+}
+
+async fn save_worker(
+	save_rx: Arc<tokio::sync::Mutex<mpsc::Receiver<(Stock, Financials)>>>,
+	repo: Repo,
+	outcome_tx: UnboundedSender<Outcome>,
+) {
+	loop {
+		let received = save_rx.lock().await.recv().await;
+		let Some((stock, financials)) = received else {
+			break
+		};
+
+		match financials.save(&repo).await {
+			Ok(()) => {
+				outcome_tx.send(Outcome::new(&stock, ScrapeItemStatus::Fetched, None)).ok();
+			},
+			Err(err) => {
+				log::error!("Failed to save financials: {:?} - Stock: `{}|{}`", err, stock.code, stock.name);
+				outcome_tx.send(Outcome::new(&stock, ScrapeItemStatus::Errored, Some(err.to_string()))).ok();
+			}
+		}
+	}
+}
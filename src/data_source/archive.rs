@@ -0,0 +1,192 @@
+use std::{env, fs::{self, File, OpenOptions}, io::{BufRead, BufReader, Read, Write}, path::{Path, PathBuf}, sync::atomic::{AtomicU64, Ordering}};
+
+use chrono::{DateTime, Local};
+use flate2::{Compression, read::GzDecoder, write::GzEncoder};
+use sha2::{Digest, Sha256};
+
+use crate::types::Error;
+
+/// 임시 파일 이름을 저장마다 다르게 하기 위한 일련번호
+static TMP_SEQ: AtomicU64 = AtomicU64::new(0);
+
+#[derive(Debug, Clone)]
+pub struct ArchiveConfig {
+	/// 아카이브 루트 디렉토리. 설정되지 않으면 응답을 보관하지 않는다
+	pub dir: Option<PathBuf>,
+}
+
+impl ArchiveConfig {
+	pub fn from_env() -> Self {
+		let dir = env::var("ARCHIVE_DIR")
+			.ok()
+			.filter(|s| !s.is_empty())
+			.map(PathBuf::from);
+		Self { dir }
+	}
+}
+
+/// 보관된 응답 하나의 색인 항목
+#[derive(Debug, Clone)]
+pub struct ArchiveEntry {
+	/// 데이터 소스 이름. 한 소스가 여러 페이지를 가져오면 `wisereport.cF1001_Y` 처럼 페이지 이름을 덧붙인다
+	pub source: String,
+	pub stock_code: String,
+	pub fetched_at: DateTime<Local>,
+	/// 응답 본문의 SHA-256 (hex)
+	pub hash: String,
+}
+
+/// 가져온 원본 응답을 보관하는 저장소
+///
+/// 본문은 내용 해시를 이름으로 gzip 압축해서 `objects/` 아래에 한 번만 저장하고,
+/// 소스/종목코드별 색인 파일(`index/{source}/{stock_code}.idx`)에 가져온 시각과 해시를 한 줄씩 덧붙인다.
+/// 파일 입출력은 비동기 작업을 막지 않도록 블로킹 스레드에서 한다.
+#[derive(Clone)]
+pub struct ResponseArchive {
+	root: PathBuf,
+}
+
+impl ResponseArchive {
+	pub fn open(root: impl AsRef<Path>) -> Result<Self, Error> {
+		let root = root.as_ref().to_path_buf();
+		fs::create_dir_all(root.join("objects"))?;
+		fs::create_dir_all(root.join("index"))?;
+		Ok(Self { root })
+	}
+
+	pub async fn store(&self, source: &str, stock_code: &str, fetched_at: DateTime<Local>, body: &str) -> Result<ArchiveEntry, Error> {
+		let (archive, source, stock_code, body) = (self.clone(), source.to_string(), stock_code.to_string(), body.to_string());
+		blocking(move || archive.store_blocking(&source, &stock_code, fetched_at, &body)).await
+	}
+
+	/// 가져온 시각 순으로 정렬된 색인 항목
+	pub async fn list(&self, source: &str, stock_code: &str) -> Result<Vec<ArchiveEntry>, Error> {
+		let (archive, source, stock_code) = (self.clone(), source.to_string(), stock_code.to_string());
+		blocking(move || archive.list_blocking(&source, &stock_code)).await
+	}
+
+	pub async fn load(&self, entry: &ArchiveEntry) -> Result<String, Error> {
+		let (archive, entry) = (self.clone(), entry.clone());
+		blocking(move || archive.load_blocking(&entry)).await
+	}
+
+	fn store_blocking(&self, source: &str, stock_code: &str, fetched_at: DateTime<Local>, body: &str) -> Result<ArchiveEntry, Error> {
+		let hash = format!("{:x}", Sha256::digest(body.as_bytes()));
+
+		let object_path = self.object_path(&hash);
+		if !object_path.exists() {
+			fs::create_dir_all(object_path.parent().unwrap())?;
+			// 동시에 같은 본문을 저장하더라도 깨진 파일이 남지 않도록 저장마다 다른 임시 파일에 쓰고 옮긴다
+			let seq = TMP_SEQ.fetch_add(1, Ordering::Relaxed);
+			let tmp_path = object_path.with_extension(format!("tmp{}.{seq}", std::process::id()));
+			let mut encoder = GzEncoder::new(File::create(&tmp_path)?, Compression::default());
+			encoder.write_all(body.as_bytes())?;
+			encoder.finish()?;
+			fs::rename(&tmp_path, &object_path)?;
+		}
+
+		let index_path = self.index_path(source, stock_code);
+		fs::create_dir_all(index_path.parent().unwrap())?;
+		let mut index = OpenOptions::new().create(true).append(true).open(&index_path)?;
+		writeln!(index, "{}\t{}", fetched_at.to_rfc3339(), hash)?;
+
+		Ok(ArchiveEntry {
+			source: source.to_string(),
+			stock_code: stock_code.to_string(),
+			fetched_at,
+			hash,
+		})
+	}
+
+	fn list_blocking(&self, source: &str, stock_code: &str) -> Result<Vec<ArchiveEntry>, Error> {
+		let index_path = self.index_path(source, stock_code);
+		if !index_path.exists() {
+			return Ok(Vec::new());
+		}
+
+		let mut entries = Vec::<ArchiveEntry>::new();
+		for line in BufReader::new(File::open(&index_path)?).lines() {
+			let line = line?;
+			let Some((fetched_at, hash)) = line.split_once('\t') else {
+				continue
+			};
+			let Ok(fetched_at) = DateTime::parse_from_rfc3339(fetched_at) else {
+				log::warn!("Invalid archive index line `{line}` in {}", index_path.display());
+				continue
+			};
+			entries.push(ArchiveEntry {
+				source: source.to_string(),
+				stock_code: stock_code.to_string(),
+				fetched_at: fetched_at.with_timezone(&Local),
+				hash: hash.trim().to_string(),
+			});
+		}
+		entries.sort_by_key(|e| e.fetched_at);
+		Ok(entries)
+	}
+
+	fn load_blocking(&self, entry: &ArchiveEntry) -> Result<String, Error> {
+		let mut decoder = GzDecoder::new(File::open(self.object_path(&entry.hash))?);
+		let mut body = String::new();
+		decoder.read_to_string(&mut body)?;
+		Ok(body)
+	}
+
+	fn object_path(&self, hash: &str) -> PathBuf {
+		self.root.join("objects").join(&hash[..2]).join(format!("{hash}.gz"))
+	}
+
+	fn index_path(&self, source: &str, stock_code: &str) -> PathBuf {
+		self.root.join("index").join(source).join(format!("{stock_code}.idx"))
+	}
+}
+
+async fn blocking<T, F>(f: F) -> Result<T, Error>
+where
+	T: Send + 'static,
+	F: FnOnce() -> Result<T, Error> + Send + 'static,
+{
+	match tokio::task::spawn_blocking(f).await {
+		Ok(result) => result,
+		Err(err) => Err(format!("Archive task failed: {err}").into()),
+	}
+}
+
+/// 아카이브가 설정되어 있으면 응답을 보관한다. 보관에 실패해도 수집은 계속한다
+pub(crate) async fn archive_response(archive: Option<&ResponseArchive>, source: &str, stock_code: &str, fetched_at: DateTime<Local>, body: &str) {
+	if let Some(archive) = archive
+		&& let Err(err) = archive.store(source, stock_code, fetched_at, body).await {
+		log::warn!("Failed to archive `{source}` page of stock `{stock_code}`: {err}");
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[tokio::test]
+	async fn store_same_body_concurrently() {
+		let dir = env::temp_dir().join(format!("stock-fn-archive-concurrent-{}", std::process::id()));
+		let archive = ResponseArchive::open(&dir).unwrap();
+		let body = "<html>점검 중입니다</html>";
+
+		let tasks: Vec<_> = (0..16)
+			.map(|i| {
+				let archive = archive.clone();
+				tokio::spawn(async move { archive.store("naver", &format!("{i:06}"), Local::now(), body).await })
+			})
+			.collect();
+		for task in tasks {
+			let entry = task.await.unwrap().unwrap();
+			assert_eq!(archive.load(&entry).await.unwrap(), body);
+		}
+
+		let leftovers: Vec<_> = fs::read_dir(archive.object_path(&format!("{:x}", Sha256::digest(body.as_bytes()))).parent().unwrap())
+			.unwrap()
+			.filter_map(|e| e.ok())
+			.filter(|e| e.file_name().to_string_lossy().contains(".tmp"))
+			.collect();
+		assert!(leftovers.is_empty());
+		fs::remove_dir_all(&dir).unwrap();
+	}
+}
@@ -0,0 +1,61 @@
+use std::{collections::HashMap, path::Path};
+
+use serde::Deserialize;
+
+use crate::types::Error;
+
+/// DART 고유번호(corp_code) ↔ 단축코드(stock_code) 매핑
+///
+/// `corpCode.xml` API 로 내려받은 zip 파일 안의 `CORPCODE.xml` 을 읽어들인다.
+#[derive(Debug, Default, Clone)]
+pub struct CorpCodes {
+	by_stock_code: HashMap<String, String>,
+}
+
+#[derive(Deserialize)]
+struct CorpCodeXml {
+	#[serde(default)]
+	list: Vec<CorpCodeItem>,
+}
+
+#[derive(Deserialize)]
+struct CorpCodeItem {
+	corp_code: String,
+	#[serde(default)]
+	stock_code: String,
+}
+
+impl CorpCodes {
+	pub fn load<P: AsRef<Path>>(path: P) -> Result<Self, Error> {
+		let xml = std::fs::read_to_string(path)?;
+		Self::parse(&xml)
+	}
+
+	pub fn parse(xml: &str) -> Result<Self, Error> {
+		let doc: CorpCodeXml = quick_xml::de::from_str(xml)?;
+		let by_stock_code = doc.list.into_iter()
+			// 비상장 법인은 stock_code 가 공백이다
+			.filter(|item| !item.stock_code.trim().is_empty())
+			.map(|item| (item.stock_code.trim().to_string(), item.corp_code.trim().to_string()))
+			.collect();
+		Ok(Self { by_stock_code })
+	}
+
+	pub fn corp_code(&self, stock_code: &str) -> Option<&str> {
+		self.by_stock_code.get(stock_code).map(|s| s.as_str())
+	}
+
+	pub fn stock_code(&self, corp_code: &str) -> Option<&str> {
+		self.by_stock_code.iter()
+			.find(|(_, cc)| cc.as_str() == corp_code)
+			.map(|(sc, _)| sc.as_str())
+	}
+
+	pub fn len(&self) -> usize {
+		self.by_stock_code.len()
+	}
+
+	pub fn is_empty(&self) -> bool {
+		self.by_stock_code.is_empty()
+	}
+}
@@ -0,0 +1,548 @@
+mod corp_code;
+
+use std::{collections::HashMap, env, sync::Arc};
+
+use async_trait::async_trait;
+use chrono::{Datelike, Local};
+use serde::Deserialize;
+
+pub use corp_code::CorpCodes;
+
+use crate::{data_source::{FinancialDataSource, HttpClient}, entities::{BalanceSheet, CashFlow, FinancialInfo, FiscalMonth}, fi_registry::{FinancialInfoRegistry, Financials}, types::{Amount, Error, FiTerm, FiscalCalendar, StatementBasis, Unit, YearMonth}};
+
+#[derive(Debug, Clone)]
+pub struct DartConfig {
+	pub api_key: String,
+	pub base_url: String,
+	/// corpCode.xml 파일 경로
+	pub corp_code_file: String,
+	/// 우선 조회할 재무제표 구분 (CFS:연결, OFS:별도)
+	pub fs_div: String,
+	/// 조회할 사업연도 수 (올해 포함)
+	pub years: u16,
+}
+
+impl DartConfig {
+	pub const DEFAULT_BASE_URL: &'static str = "https://opendart.fss.or.kr";
+
+	/// `DART_API_KEY` 가 설정되지 않았으면 `None`
+	pub fn from_env() -> Option<Self> {
+		let api_key = env::var("DART_API_KEY").ok()?;
+		let base_url = env::var("DART_BASE_URL")
+			.unwrap_or_else(|_| Self::DEFAULT_BASE_URL.to_string());
+		let corp_code_file = env::var("DART_CORP_CODE_FILE")
+			.unwrap_or_else(|_| "CORPCODE.xml".to_string());
+		let fs_div = env::var("DART_FS_DIV")
+			.unwrap_or_else(|_| "CFS".to_string());
+		let years = env::var("DART_YEARS")
+			.ok()
+			.and_then(|s| s.parse().ok())
+			.unwrap_or(3);
+		Some(Self {
+			api_key,
+			base_url,
+			corp_code_file,
+			fs_div,
+			years,
+		})
+	}
+}
+
+/// 보고서 코드
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+enum ReportCode {
+	/// 1분기보고서
+	Q1,
+	/// 반기보고서
+	Half,
+	/// 3분기보고서
+	Q3,
+	/// 사업보고서
+	Annual,
+}
+
+impl ReportCode {
+	const ALL: [ReportCode; 4] = [ReportCode::Q1, ReportCode::Half, ReportCode::Q3, ReportCode::Annual];
+
+	fn code(&self) -> &'static str {
+		match self {
+			ReportCode::Q1 => "11013",
+			ReportCode::Half => "11012",
+			ReportCode::Q3 => "11014",
+			ReportCode::Annual => "11011",
+		}
+	}
+
+	/// 사업연도 보고서의 보고기간 종료 년월.
+	///
+	/// 12월 결산법인이 아니면 사업연도는 회계연도가 시작하는 해이다 (예: 3월 결산법인의 2024 사업연도는 2025.03 에 끝난다).
+	fn period_end(&self, calendar: FiscalCalendar, business_year: u16) -> YearMonth {
+		let fiscal_year = if calendar.fiscal_month == 12 { business_year } else { business_year + 1 };
+		let quarters_left = match self {
+			ReportCode::Q1 => 3,
+			ReportCode::Half => 2,
+			ReportCode::Q3 => 1,
+			ReportCode::Annual => 0,
+		};
+		calendar.fiscal_year_end(fiscal_year).sub_quarters(quarters_left)
+	}
+}
+
+#[derive(Deserialize)]
+struct DartResponse {
+	status: String,
+	message: String,
+	#[serde(default)]
+	list: Vec<DartAccount>,
+}
+
+/// 기업개황 (`company.json`)
+#[derive(Deserialize)]
+struct DartCompany {
+	status: String,
+	message: String,
+	/// 결산월 (MM)
+	#[serde(default)]
+	acc_mt: String,
+}
+
+#[derive(Debug, Deserialize)]
+struct DartAccount {
+	/// 재무제표구분 (BS:재무상태표, IS:손익계산서, CIS:포괄손익계산서, CF:현금흐름표, SCE:자본변동표)
+	sj_div: String,
+	account_id: String,
+	account_nm: String,
+	/// 당기금액 (분기보고서의 손익계산서는 3개월 금액, 현금흐름표는 누적 금액)
+	#[serde(default)]
+	thstrm_amount: Option<String>,
+}
+
+/// OpenDART 단일회사 전체 재무제표 (`fnlttSinglAcntAll`)
+pub struct DartDataSource {
+	conf: DartConfig,
+	corp_codes: CorpCodes,
+	/// 저장된 종목별 결산월
+	fiscal_months: HashMap<String, FiscalCalendar>,
+	http: Arc<HttpClient>,
+}
+
+impl DartDataSource {
+	pub const NAME: &'static str = "dart";
+
+	pub fn new(conf: &DartConfig, http: Arc<HttpClient>) -> Result<Self, Error> {
+		let corp_codes = CorpCodes::load(&conf.corp_code_file)
+			.map_err(|e| format!("Can't load DART corp codes from `{}`: {}", conf.corp_code_file, e))?;
+		log::info!("{} DART corp codes loaded", corp_codes.len());
+		Ok(Self::with_corp_codes(conf, corp_codes, http))
+	}
+
+	pub fn with_corp_codes(conf: &DartConfig, corp_codes: CorpCodes, http: Arc<HttpClient>) -> Self {
+		Self {
+			conf: conf.clone(),
+			corp_codes,
+			fiscal_months: HashMap::new(),
+			http,
+		}
+	}
+
+	/// 기업개황에서 결산월을 얻지 못했을 때 쓸 저장된 결산월
+	pub fn with_fiscal_months(mut self, fiscal_months: &[FiscalMonth]) -> Self {
+		self.fiscal_months = fiscal_months.iter()
+			.map(|fm| (fm.stock_code.clone(), fm.calendar()))
+			.collect();
+		self
+	}
+
+	/// 기업개황의 결산월. 없으면 저장된 결산월
+	async fn fiscal_calendar(&self, stock_code: &str, corp_code: &str) -> Result<FiscalCalendar, Error> {
+		let stored = self.fiscal_months.get(stock_code).copied();
+		match self.query_fiscal_calendar(corp_code).await {
+			Ok(calendar) => {
+				if let Some(stored) = stored.filter(|stored| *stored != calendar) {
+					log::info!("Stock `{stock_code}` reports fiscal month {} on DART (stored: {})", calendar.fiscal_month, stored.fiscal_month);
+				}
+				Ok(calendar)
+			}
+			Err(err) => match stored {
+				Some(stored) => {
+					log::warn!("Using stored fiscal month {} of stock `{stock_code}`: {err}", stored.fiscal_month);
+					Ok(stored)
+				}
+				None => Err(err),
+			},
+		}
+	}
+
+	async fn query_report(&self, corp_code: &str, year: u16, report: ReportCode, fs_div: &str) -> Result<Option<Vec<DartAccount>>, Error> {
+		let year = year.to_string();
+		let params = [
+			("crtfc_key", self.conf.api_key.as_str()),
+			("corp_code", corp_code),
+			("bsns_year", year.as_str()),
+			("reprt_code", report.code()),
+			("fs_div", fs_div),
+		];
+		let url = format!("{}/api/fnlttSinglAcntAll.json", self.conf.base_url.trim_end_matches('/'));
+		let url = reqwest::Url::parse_with_params(&url, params)
+			.map_err(|e| e.to_string())?;
+		let text = self.http.get_text(url).await?;
+		let resp: DartResponse = serde_json::from_str(&text)?;
+		match resp.status.as_str() {
+			"000" => Ok(Some(resp.list)),
+			// 조회된 데이타가 없습니다
+			"013" => Ok(None),
+			_ => Err(format!("DART error {}: {}", resp.status, resp.message).into()),
+		}
+	}
+
+	/// 기업개황의 결산월
+	async fn query_fiscal_calendar(&self, corp_code: &str) -> Result<FiscalCalendar, Error> {
+		let params = [
+			("crtfc_key", self.conf.api_key.as_str()),
+			("corp_code", corp_code),
+		];
+		let url = format!("{}/api/company.json", self.conf.base_url.trim_end_matches('/'));
+		let url = reqwest::Url::parse_with_params(&url, params)
+			.map_err(|e| e.to_string())?;
+		let text = self.http.get_text(url).await?;
+		let resp: DartCompany = serde_json::from_str(&text)?;
+		if resp.status != "000" {
+			return Err(format!("DART error {}: {}", resp.status, resp.message).into());
+		}
+		let fiscal_month = resp.acc_mt.trim().parse::<u8>()
+			.map_err(|_| format!("Invalid DART acc_mt `{}` of corp `{corp_code}`", resp.acc_mt))?;
+		FiscalCalendar::new(fiscal_month)
+	}
+
+	/// 설정된 재무제표 구분으로 먼저 조회하고, 데이터가 없으면 다른 구분으로 다시 조회한다
+	async fn query_statements(&self, corp_code: &str, year: u16, report: ReportCode) -> Result<Option<(StatementBasis, Vec<DartAccount>)>, Error> {
+		let preferred = self.conf.fs_div.as_str();
+		if let Some(accounts) = self.query_report(corp_code, year, report, preferred).await? {
+			return Ok(Some((fs_div_basis(preferred), accounts)));
+		}
+		let fallback = if preferred == "CFS" { "OFS" } else { "CFS" };
+		let accounts = self.query_report(corp_code, year, report, fallback).await?;
+		Ok(accounts.map(|accounts| (fs_div_basis(fallback), accounts)))
+	}
+}
+
+#[async_trait]
+impl FinancialDataSource for DartDataSource {
+	fn name(&self) -> &'static str {
+		Self::NAME
+	}
+
+	async fn query_stock_financials(&self, stock_code: &str) -> Result<Financials, Error> {
+		let Some(corp_code) = self.corp_codes.corp_code(stock_code) else {
+			return Err(format!("DART corp_code not found for stock `{stock_code}`").into());
+		};
+
+		let calendar = self.fiscal_calendar(stock_code, corp_code).await?;
+		let mut financials = Financials::new(Self::NAME, stock_code);
+		let this_year = Local::now().year() as u16;
+		let first_year = this_year.saturating_sub(self.conf.years.saturating_sub(1));
+		for year in first_year..=this_year {
+			for report in ReportCode::ALL {
+				let Some((basis, accounts)) = self.query_statements(corp_code, year, report).await? else {
+					continue
+				};
+				let year_month = report.period_end(calendar, year);
+				let registry = match report {
+					ReportCode::Annual => &mut financials.annuals,
+					_ => &mut financials.quarters,
+				};
+				register_accounts(registry, year_month, report, &accounts);
+				registry.set_basis(year_month, basis);
+
+				let term = match report {
+					ReportCode::Annual => FiTerm::Annual,
+					_ => FiTerm::Quarter,
+				};
+				financials.balance_sheets.extend(balance_sheet(stock_code, term, basis, year_month, &accounts));
+				financials.cash_flows.extend(cash_flow(stock_code, term, basis, year_month, &accounts));
+			}
+		}
+		Ok(financials)
+	}
+}
+
+/// OFS 는 별도, 그 외는 연결
+fn fs_div_basis(fs_div: &str) -> StatementBasis {
+	if fs_div == "OFS" {
+		StatementBasis::Separate
+	} else {
+		StatementBasis::Consolidated
+	}
+}
+
+fn register_accounts(registry: &mut FinancialInfoRegistry, year_month: YearMonth, report: ReportCode, accounts: &[DartAccount]) {
+	let sales = find_amount(accounts, &["IS", "CIS"], &["ifrs-full_Revenue", "ifrs_Revenue"], &["매출액", "수익(매출액)", "영업수익"]);
+	let profit = find_amount(accounts, &["IS", "CIS"], &["dart_OperatingIncomeLoss"], &["영업이익", "영업이익(손실)"]);
+	let net_income = find_amount(accounts, &["IS", "CIS"], &["ifrs-full_ProfitLoss", "ifrs_ProfitLoss"], &["당기순이익", "당기순이익(손실)"]);
+	let owners_net_income = find_amount(accounts, &["IS", "CIS"], &["ifrs-full_ProfitLossAttributableToOwnersOfParent"], &[]);
+	let eps = find_amount(accounts, &["IS", "CIS"], &["ifrs-full_BasicEarningsLossPerShare"], &["기본주당이익", "기본주당이익(손실)"]);
+	let liabilities = find_amount(accounts, &["BS"], &["ifrs-full_Liabilities"], &["부채총계"]);
+	let equity = find_amount(accounts, &["BS"], &["ifrs-full_Equity"], &["자본총계"]);
+	let owners_equity = find_amount(accounts, &["BS"], &["ifrs-full_EquityAttributableToOwnersOfParent"], &[]);
+	let current_assets = find_amount(accounts, &["BS"], &["ifrs-full_CurrentAssets"], &["유동자산"]);
+	let inventories = find_amount(accounts, &["BS"], &["ifrs-full_Inventories"], &["재고자산"]);
+	let current_liabilities = find_amount(accounts, &["BS"], &["ifrs-full_CurrentLiabilities"], &["유동부채"]);
+
+	let ratio = |num: Option<Amount>, den: Option<Amount>| {
+		num.zip(den).and_then(|(num, den)| num.checked_div(den)).map(|v| v * Amount::ONE_HUNDRED)
+	};
+
+	registry.register(year_month, "매출액", sales, Unit::Won);
+	registry.register(year_month, "영업이익", profit, Unit::Won);
+	registry.register(year_month, "당기순이익", net_income, Unit::Won);
+	registry.register(year_month, "영업이익률", ratio(profit, sales), Unit::Percent);
+	registry.register(year_month, "순이익률", ratio(net_income, sales), Unit::Percent);
+	registry.register(year_month, "부채비율", ratio(liabilities, equity), Unit::Percent);
+	registry.register(year_month, "당좌비율", ratio(current_assets.map(|ca| ca - inventories.unwrap_or(Amount::ZERO)), current_liabilities), Unit::Percent);
+	registry.register(year_month, "EPS(원)", eps, Unit::Won);
+	// 분기 ROE 는 연환산 기준이 제각각이라 사업보고서에서만 계산한다
+	if report == ReportCode::Annual {
+		registry.register(year_month, "ROE(지배주주)", ratio(owners_net_income.or(net_income), owners_equity.or(equity)), Unit::Percent);
+	}
+}
+
+/// 재무상태표 주요 항목. 하나도 없으면 `None`
+fn balance_sheet(stock_code: &str, term: FiTerm, basis: StatementBasis, year_month: YearMonth, accounts: &[DartAccount]) -> Option<BalanceSheet> {
+	let find = |account_ids: &[&str], account_names: &[&str]| {
+		find_amount(accounts, &["BS"], account_ids, account_names)
+	};
+	let borrowings = [
+		find(&["ifrs-full_ShorttermBorrowings"], &["단기차입금"]),
+		find(&["ifrs-full_CurrentPortionOfLongtermBorrowings"], &["유동성장기차입금", "유동성장기부채"]),
+		find(&["ifrs-full_LongtermBorrowings"], &["장기차입금"]),
+		find(&["ifrs-full_BondsIssued", "dart_BondsIssued"], &["사채"]),
+	].into_iter().flatten().reduce(|acc, v| acc + v);
+
+	let balance_sheet = BalanceSheet {
+		stock_code: stock_code.to_string(),
+		term,
+		basis,
+		year_month,
+		total_assets: to_amount_unit(find(&["ifrs-full_Assets"], &["자산총계"])),
+		total_liabilities: to_amount_unit(find(&["ifrs-full_Liabilities"], &["부채총계"])),
+		total_equity: to_amount_unit(find(&["ifrs-full_Equity"], &["자본총계"])),
+		owners_equity: to_amount_unit(find(&["ifrs-full_EquityAttributableToOwnersOfParent"], &["지배기업의 소유주에게 귀속되는 자본", "지배기업 소유주지분"])),
+		cash: to_amount_unit(find(&["ifrs-full_CashAndCashEquivalents"], &["현금및현금성자산"])),
+		borrowings: to_amount_unit(borrowings),
+	};
+	let figures = [balance_sheet.total_assets, balance_sheet.total_liabilities, balance_sheet.total_equity, balance_sheet.owners_equity, balance_sheet.cash, balance_sheet.borrowings];
+	figures.iter().any(Option::is_some).then_some(balance_sheet)
+}
+
+/// 현금흐름표 주요 항목. 하나도 없으면 `None`
+fn cash_flow(stock_code: &str, term: FiTerm, basis: StatementBasis, year_month: YearMonth, accounts: &[DartAccount]) -> Option<CashFlow> {
+	let find = |account_ids: &[&str], account_names: &[&str]| {
+		find_amount(accounts, &["CF"], account_ids, account_names)
+	};
+	let operating = find(&["ifrs-full_CashFlowsFromUsedInOperatingActivities"], &["영업활동현금흐름", "영업활동으로 인한 현금흐름"]);
+	// 지출이 음수로 공시되기도 하고 양수로 공시되기도 한다
+	let capex = find(&["ifrs-full_PurchaseOfPropertyPlantAndEquipment"], &["유형자산의 취득", "유형자산의취득"])
+		.map(|v| v.abs());
+
+	let cash_flow = CashFlow {
+		stock_code: stock_code.to_string(),
+		term,
+		basis,
+		year_month,
+		operating: to_amount_unit(operating),
+		investing: to_amount_unit(find(&["ifrs-full_CashFlowsFromUsedInInvestingActivities"], &["투자활동현금흐름", "투자활동으로 인한 현금흐름"])),
+		financing: to_amount_unit(find(&["ifrs-full_CashFlowsFromUsedInFinancingActivities"], &["재무활동현금흐름", "재무활동으로 인한 현금흐름"])),
+		capex: to_amount_unit(capex),
+		fcf: to_amount_unit(CashFlow::free_cash_flow(operating, capex)),
+		// 분기보고서의 현금흐름표는 회계연도 누적 금액만 공시된다
+		cumulative: term == FiTerm::Quarter,
+	};
+	let figures = [cash_flow.operating, cash_flow.investing, cash_flow.financing, cash_flow.capex];
+	figures.iter().any(Option::is_some).then_some(cash_flow)
+}
+
+/// 원 단위 금액을 `FinancialInfo` 의 금액 단위로 바꾼다
+fn to_amount_unit(value: Option<Amount>) -> Option<Amount> {
+	value.and_then(|v| Unit::Won.convert(v, FinancialInfo::AMOUNT_UNIT))
+		.map(|v| v.round_dp(FinancialInfo::FIGURE_SCALE))
+}
+
+fn find_amount(accounts: &[DartAccount], sj_divs: &[&str], account_ids: &[&str], account_names: &[&str]) -> Option<Amount> {
+	accounts.iter()
+		.filter(|a| sj_divs.contains(&a.sj_div.as_str()))
+		.find(|a| account_ids.contains(&a.account_id.as_str()) || account_names.contains(&a.account_nm.trim()))
+		.and_then(|a| a.thstrm_amount.as_ref())
+		.and_then(|amount| amount.replace(",", "").trim().parse::<Amount>().ok())
+}
+
+#[cfg(test)]
+mod tests {
+	use std::{collections::HashMap, time::Duration};
+
+	use tokio::{io::{AsyncReadExt, AsyncWriteExt}, net::TcpListener};
+
+	use crate::data_source::HttpConfig;
+
+	use super::*;
+
+	macro_rules! fixture {
+		($name:literal) => {
+			include_str!(concat!(env!("CARGO_MANIFEST_DIR"), "/tests/fixtures/dart/", $name))
+		};
+	}
+
+	/// 녹화된 응답을 돌려주는 OpenDART 흉내. CFS 는 사업보고서만, OFS 는 1분기보고서만 있다
+	fn route(path: &str, query: &HashMap<String, String>) -> &'static str {
+		let param = |name: &str| query.get(name).map(String::as_str).unwrap_or("");
+		match path {
+			"/api/company.json" => match param("corp_code") {
+				"00126380" => fixture!("company_december.json"),
+				"00104856" => fixture!("company_march.json"),
+				_ => r#"{"status":"013","message":"조회된 데이타가 없습니다."}"#,
+			},
+			"/api/fnlttSinglAcntAll.json" => match (param("reprt_code"), param("fs_div")) {
+				("11011", "CFS") => fixture!("fnlttSinglAcntAll_annual_cfs.json"),
+				("11013", "OFS") => fixture!("fnlttSinglAcntAll_q1_ofs.json"),
+				_ => fixture!("no_data.json"),
+			},
+			_ => r#"{"status":"100","message":"unknown path"}"#,
+		}
+	}
+
+	/// 로컬 포트에서 `route` 로 응답하는 서버를 띄우고 base url 을 돌려준다
+	async fn serve() -> String {
+		let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+		let addr = listener.local_addr().unwrap();
+		tokio::spawn(async move {
+			loop {
+				let Ok((mut socket, _)) = listener.accept().await else {
+					break
+				};
+				tokio::spawn(async move {
+					let mut buf = Vec::new();
+					let mut chunk = [0u8; 1024];
+					while !buf.windows(4).any(|w| w == b"\r\n\r\n") {
+						match socket.read(&mut chunk).await {
+							Ok(0) | Err(_) => return,
+							Ok(n) => buf.extend_from_slice(&chunk[..n]),
+						}
+					}
+					let request = String::from_utf8_lossy(&buf);
+					let target = request.split_whitespace().nth(1).unwrap_or("/");
+					let url = reqwest::Url::parse(&format!("http://localhost{target}")).unwrap();
+					let query: HashMap<String, String> = url.query_pairs().into_owned().collect();
+					let body = route(url.path(), &query);
+					let resp = format!("HTTP/1.1 200 OK\r\nContent-Type: application/json; charset=utf-8\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}", body.len(), body);
+					socket.write_all(resp.as_bytes()).await.ok();
+				});
+			}
+		});
+		format!("http://{addr}")
+	}
+
+	async fn data_source() -> DartDataSource {
+		data_source_with(&[]).await
+	}
+
+	async fn data_source_with(fiscal_months: &[FiscalMonth]) -> DartDataSource {
+		let conf = DartConfig {
+			api_key: "test".to_string(),
+			base_url: serve().await,
+			corp_code_file: String::new(),
+			fs_div: "CFS".to_string(),
+			years: 1,
+		};
+		let http = HttpConfig {
+			max_retries: 0,
+			default_rate_limit: Duration::from_millis(1),
+			..Default::default()
+		};
+		let corp_codes = CorpCodes::parse(fixture!("corpCode.xml")).unwrap();
+		DartDataSource::with_corp_codes(&conf, corp_codes, Arc::new(HttpClient::new(&http).unwrap()))
+			.with_fiscal_months(fiscal_months)
+	}
+
+	fn amount(s: &str) -> Option<Amount> {
+		Some(s.parse().unwrap())
+	}
+
+	#[test]
+	fn parse_corp_codes() {
+		let corp_codes = CorpCodes::parse(fixture!("corpCode.xml")).unwrap();
+		// 비상장 법인은 빠진다
+		assert_eq!(corp_codes.len(), 3);
+		assert_eq!(corp_codes.corp_code("005930"), Some("00126380"));
+		assert_eq!(corp_codes.stock_code("00104856"), Some("071050"));
+	}
+
+	#[test]
+	fn report_period_end() {
+		let december = FiscalCalendar::DECEMBER;
+		assert_eq!(ReportCode::Q1.period_end(december, 2024), YearMonth::new(2024, 3));
+		assert_eq!(ReportCode::Annual.period_end(december, 2024), YearMonth::new(2024, 12));
+		let march = FiscalCalendar::new(3).unwrap();
+		assert_eq!(ReportCode::Q1.period_end(march, 2024), YearMonth::new(2024, 6));
+		assert_eq!(ReportCode::Q3.period_end(march, 2024), YearMonth::new(2024, 12));
+		assert_eq!(ReportCode::Annual.period_end(march, 2024), YearMonth::new(2025, 3));
+	}
+
+	#[tokio::test]
+	async fn query_stock_financials() {
+		let source = data_source().await;
+		let financials = source.query_stock_financials("005930").await.unwrap();
+		let year = Local::now().year() as u16;
+
+		assert_eq!(financials.annuals.len(), 1);
+		let annual = &financials.annuals.list[0];
+		assert_eq!(annual.year_month, YearMonth::new(year, 12));
+		assert_eq!(annual.basis, StatementBasis::Consolidated);
+		// 원 단위 금액은 억원으로 바뀐다
+		assert_eq!(annual.sales, amount("2589354.94"));
+		assert_eq!(annual.profit, amount("65669.76"));
+		assert_eq!(annual.net_income, amount("154871"));
+		assert_eq!(annual.eps, amount("2131"));
+		assert_eq!(annual.operating_margin, amount("2.54"));
+		assert_eq!(annual.debt_ratio, amount("25.36"));
+		assert_eq!(annual.roe, amount("4.10"));
+
+		// 1분기는 연결 재무제표가 없어서 별도로 다시 조회한다
+		assert_eq!(financials.quarters.len(), 1);
+		let quarter = &financials.quarters.list[0];
+		assert_eq!(quarter.year_month, YearMonth::new(year, 3));
+		assert_eq!(quarter.basis, StatementBasis::Separate);
+		assert_eq!(quarter.sales, amount("507432.62"));
+		assert_eq!(quarter.roe, None);
+	}
+
+	#[tokio::test]
+	async fn query_stock_financials_of_march_filer() {
+		let source = data_source().await;
+		let financials = source.query_stock_financials("071050").await.unwrap();
+		let year = Local::now().year() as u16;
+
+		assert_eq!(financials.annuals.list[0].year_month, YearMonth::new(year + 1, 3));
+		assert_eq!(financials.quarters.list[0].year_month, YearMonth::new(year, 6));
+	}
+
+	#[tokio::test]
+	async fn stored_fiscal_month() {
+		// 기업개황이 없는 종목
+		assert!(data_source().await.query_stock_financials("000660").await.is_err());
+
+		let fiscal_month = FiscalMonth {
+			stock_code: "000660".to_string(),
+			month: 3,
+			since: YearMonth::new(2020, 3),
+		};
+		let source = data_source_with(&[fiscal_month]).await;
+		let financials = source.query_stock_financials("000660").await.unwrap();
+		let year = Local::now().year() as u16;
+		assert_eq!(financials.annuals.list[0].year_month, YearMonth::new(year + 1, 3));
+	}
+
+	#[tokio::test]
+	async fn unknown_stock_code() {
+		let source = data_source().await;
+		assert!(source.query_stock_financials("000000").await.is_err());
+	}
+}
@@ -0,0 +1,225 @@
+use std::{collections::HashMap, env, sync::{Arc, Mutex}, time::{Duration, SystemTime, UNIX_EPOCH}};
+
+use ratelimit::Ratelimiter;
+use reqwest::{StatusCode, Url, header::RETRY_AFTER};
+
+use crate::types::Error;
+
+/// 호스트별 기본 요청 간격
+const DEFAULT_RATE_LIMITS: [(&str, u64); 3] = [
+	("finance.naver.com", 500),
+	("fchart.stock.naver.com", 500),
+	("opendart.fss.or.kr", 100),
+];
+
+#[derive(Debug, Clone)]
+pub struct HttpConfig {
+	pub timeout: Duration,
+	pub connect_timeout: Duration,
+	/// 최초 요청 이후 재시도 횟수
+	pub max_retries: u32,
+	pub retry_base_delay: Duration,
+	pub retry_max_delay: Duration,
+	pub user_agent: String,
+	pub proxy: Option<String>,
+	/// 호스트별 최소 요청 간격
+	pub rate_limits: HashMap<String, Duration>,
+	/// `rate_limits` 에 없는 호스트의 최소 요청 간격
+	pub default_rate_limit: Duration,
+}
+
+impl Default for HttpConfig {
+	fn default() -> Self {
+		Self {
+			timeout: Duration::from_secs(10),
+			connect_timeout: Duration::from_secs(5),
+			max_retries: 3,
+			retry_base_delay: Duration::from_millis(500),
+			retry_max_delay: Duration::from_secs(30),
+			user_agent: concat!("stock-fn-scraper/", env!("CARGO_PKG_VERSION")).to_string(),
+			proxy: None,
+			rate_limits: DEFAULT_RATE_LIMITS.iter()
+				.map(|(host, ms)| (host.to_string(), Duration::from_millis(*ms)))
+				.collect(),
+			default_rate_limit: Duration::from_millis(500),
+		}
+	}
+}
+
+impl HttpConfig {
+	pub fn from_env() -> Self {
+		let mut conf = Self::default();
+		let millis = |name: &str| env::var(name).ok().and_then(|s| s.parse::<u64>().ok()).map(Duration::from_millis);
+
+		if let Some(timeout) = millis("HTTP_TIMEOUT_MS") {
+			conf.timeout = timeout;
+		}
+		if let Some(timeout) = millis("HTTP_CONNECT_TIMEOUT_MS") {
+			conf.connect_timeout = timeout;
+		}
+		if let Some(retries) = env::var("HTTP_MAX_RETRIES").ok().and_then(|s| s.parse().ok()) {
+			conf.max_retries = retries;
+		}
+		if let Some(delay) = millis("HTTP_RETRY_BASE_MS") {
+			conf.retry_base_delay = delay;
+		}
+		if let Some(delay) = millis("HTTP_RETRY_MAX_MS") {
+			conf.retry_max_delay = delay;
+		}
+		if let Ok(user_agent) = env::var("HTTP_USER_AGENT") {
+			conf.user_agent = user_agent;
+		}
+		conf.proxy = env::var("HTTP_PROXY").ok().filter(|s| !s.is_empty());
+		if let Some(interval) = millis("HTTP_RATE_LIMIT_MS") {
+			conf.default_rate_limit = interval;
+		}
+		// 예: `finance.naver.com=500,opendart.fss.or.kr=100`
+		if let Ok(rate_limits) = env::var("HTTP_RATE_LIMITS") {
+			for entry in rate_limits.split(',').map(|s| s.trim()).filter(|s| !s.is_empty()) {
+				match entry.split_once('=').and_then(|(host, ms)| Some((host.trim(), ms.trim().parse::<u64>().ok()?))) {
+					Some((host, ms)) => {
+						conf.rate_limits.insert(host.to_string(), Duration::from_millis(ms));
+					}
+					None => log::warn!("Invalid HTTP_RATE_LIMITS entry `{entry}` ignored"),
+				}
+			}
+		}
+		conf
+	}
+}
+
+#[derive(Debug)]
+pub enum HttpError {
+	/// 2xx 가 아닌 응답
+	Status { url: String, status: u16 },
+	/// 연결 실패, 타임아웃 등
+	Transport { url: String, message: String },
+}
+
+impl HttpError {
+	fn is_retryable(&self) -> bool {
+		match self {
+			HttpError::Status { status, .. } => *status == StatusCode::TOO_MANY_REQUESTS.as_u16() || (500..600).contains(status),
+			HttpError::Transport { .. } => true,
+		}
+	}
+}
+
+impl std::error::Error for HttpError {}
+
+impl std::fmt::Display for HttpError {
+	fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+		match self {
+			HttpError::Status { url, status } => write!(f, "HTTP {status} from {url}"),
+			HttpError::Transport { url, message } => write!(f, "Request to {url} failed: {message}"),
+		}
+	}
+}
+
+impl From<HttpError> for Error {
+	fn from(err: HttpError) -> Self {
+		Error {
+			message: err.to_string(),
+		}
+	}
+}
+
+/// 커넥션을 재사용하는 공용 HTTP 클라이언트
+///
+/// 호스트별 요청 간격을 지키고, 전송 오류와 5xx/429 응답은 지수 백오프로 재시도한다.
+pub struct HttpClient {
+	client: reqwest::Client,
+	conf: HttpConfig,
+	ratelimiters: Mutex<HashMap<String, Arc<Ratelimiter>>>,
+}
+
+impl HttpClient {
+	pub fn new(conf: &HttpConfig) -> Result<Self, Error> {
+		let mut builder = reqwest::Client::builder()
+			.timeout(conf.timeout)
+			.connect_timeout(conf.connect_timeout)
+			.user_agent(conf.user_agent.as_str());
+		if let Some(proxy) = conf.proxy.as_ref() {
+			let proxy = reqwest::Proxy::all(proxy)
+				.map_err(|e| format!("Invalid proxy `{proxy}`: {e}"))?;
+			builder = builder.proxy(proxy);
+		}
+		let client = builder.build()
+			.map_err(|e| e.to_string())?;
+		Ok(Self {
+			client,
+			conf: conf.clone(),
+			ratelimiters: Mutex::new(HashMap::new()),
+		})
+	}
+
+	pub async fn get_text(&self, url: Url) -> Result<String, HttpError> {
+		let mut attempt = 0;
+		loop {
+			let (result, retry_after) = self.try_get_text(&url).await;
+			let err = match result {
+				Ok(text) => return Ok(text),
+				Err(err) if err.is_retryable() && attempt < self.conf.max_retries => err,
+				Err(err) => return Err(err),
+			};
+
+			let delay = retry_after.unwrap_or_else(|| self.backoff(attempt));
+			attempt += 1;
+			log::warn!("{err} - retrying in {}ms ({attempt}/{})", delay.as_millis(), self.conf.max_retries);
+			tokio::time::sleep(delay).await;
+		}
+	}
+
+	async fn try_get_text(&self, url: &Url) -> (Result<String, HttpError>, Option<Duration>) {
+		self.wait_for(url.host_str().unwrap_or("")).await;
+
+		let transport_error = |e: reqwest::Error| HttpError::Transport { url: url.to_string(), message: e.to_string() };
+		let resp = match self.client.get(url.clone()).send().await {
+			Ok(resp) => resp,
+			Err(e) => return (Err(transport_error(e)), None),
+		};
+
+		let status = resp.status();
+		if !status.is_success() {
+			let retry_after = resp.headers().get(RETRY_AFTER)
+				.and_then(|v| v.to_str().ok())
+				.and_then(|v| v.parse::<u64>().ok())
+				.map(|secs| Duration::from_secs(secs).min(self.conf.retry_max_delay));
+			return (Err(HttpError::Status { url: url.to_string(), status: status.as_u16() }), retry_after);
+		}
+
+		(resp.text().await.map_err(transport_error), None)
+	}
+
+	async fn wait_for(&self, host: &str) {
+		let ratelimiter = self.ratelimiter(host);
+		while let Err(dur) = ratelimiter.try_wait() {
+			tokio::time::sleep(dur).await;
+		}
+	}
+
+	fn ratelimiter(&self, host: &str) -> Arc<Ratelimiter> {
+		let mut ratelimiters = self.ratelimiters.lock().unwrap();
+		if let Some(ratelimiter) = ratelimiters.get(host) {
+			return ratelimiter.clone();
+		}
+
+		let interval = self.conf.rate_limits.get(host).copied().unwrap_or(self.conf.default_rate_limit);
+		let ratelimiter = Arc::new(Ratelimiter::builder(1, interval.max(Duration::from_millis(1)))
+			.initial_available(1)
+			.build()
+			.unwrap());
+		ratelimiters.insert(host.to_string(), ratelimiter.clone());
+		ratelimiter
+	}
+
+	/// 지수 백오프에 [50%, 100%] 구간의 지터를 더한 대기시간
+	fn backoff(&self, attempt: u32) -> Duration {
+		let delay = self.conf.retry_base_delay
+			.saturating_mul(2u32.saturating_pow(attempt))
+			.min(self.conf.retry_max_delay);
+		let nanos = SystemTime::now().duration_since(UNIX_EPOCH).map(|d| d.subsec_nanos()).unwrap_or(0);
+		let jitter = (nanos % 1000) as f64 / 1000.0;
+		delay.mul_f64(0.5 + jitter * 0.5)
+	}
+}
@@ -0,0 +1,182 @@
+use std::path::Path;
+
+use chrono::NaiveDate;
+use serde::Deserialize;
+
+use crate::{entities::ProductItem, types::{Error, Market}};
+
+/// KRX 정보데이터시스템 `전종목 기본정보` CSV 의 한 행
+#[derive(Deserialize)]
+struct StockRow {
+	#[serde(rename = "표준코드")]
+	std_code: String,
+	#[serde(rename = "단축코드")]
+	code: String,
+	#[serde(rename = "한글 종목약명", alias = "한글종목약명")]
+	name: String,
+	#[serde(rename = "상장일")]
+	list_date: String,
+	#[serde(rename = "시장구분")]
+	market: String,
+	#[serde(rename = "증권구분")]
+	secu_group: String,
+	#[serde(rename = "소속부")]
+	sect: String,
+	#[serde(rename = "주식종류")]
+	kind: String,
+	#[serde(rename = "액면가")]
+	par: String,
+	#[serde(rename = "상장주식수")]
+	list_shares: String,
+}
+
+/// KRX 정보데이터시스템 `ETF 전종목 기본정보` CSV 의 한 행
+#[derive(Deserialize)]
+struct EtfRow {
+	#[serde(rename = "표준코드")]
+	std_code: String,
+	#[serde(rename = "단축코드")]
+	code: String,
+	#[serde(rename = "한글종목약명", alias = "한글 종목약명")]
+	name: String,
+	#[serde(rename = "상장일")]
+	list_date: String,
+	#[serde(rename = "기초지수명")]
+	obj_idx: String,
+	#[serde(rename = "지수산출기관")]
+	idx_inst: String,
+	#[serde(rename = "추적배수")]
+	idx_multiplier: String,
+	#[serde(rename = "복제방법")]
+	replica_method: String,
+	#[serde(rename = "기초시장분류")]
+	idx_market: String,
+	#[serde(rename = "기초자산분류")]
+	idx_asset: String,
+	#[serde(rename = "상장좌수")]
+	list_shares: String,
+	#[serde(rename = "운용사")]
+	op_company: String,
+	#[serde(rename = "총보수")]
+	fee_rate: String,
+	#[serde(rename = "과세유형")]
+	tax_type: String,
+}
+
+/// `전종목 기본정보` CSV 에서 KOSPI/KOSDAQ 종목을 읽는다 (KONEX 는 제외)
+pub fn load_stock_master<P: AsRef<Path>>(path: P, info_date: NaiveDate) -> Result<Vec<ProductItem>, Error> {
+	let text = read_csv_text(path)?;
+	let mut reader = csv::Reader::from_reader(text.as_bytes());
+
+	let mut items = Vec::<ProductItem>::new();
+	for row in reader.deserialize::<StockRow>() {
+		let row = row?;
+		let market = match row.market.trim() {
+			"KOSPI" => Market::KOSPI,
+			"KOSDAQ" | "KOSDAQ GLOBAL" => Market::KOSDAQ,
+			_ => continue,
+		};
+		items.push(ProductItem {
+			code: row.code.trim().to_string(),
+			info_date,
+			name: row.name.trim().to_string(),
+			market,
+			std_code: non_empty(&row.std_code),
+			list_date: parse_date(&row.list_date),
+			kind: non_empty(&row.kind),
+			secu_group: non_empty(&row.secu_group),
+			sect: non_empty(&row.sect),
+			// 무액면 종목은 액면가가 숫자가 아니다
+			par: parse_number(&row.par),
+			list_shares: parse_number(&row.list_shares),
+			etf_obj_idx: None,
+			etf_idx_inst: None,
+			etf_idx_multiplier: None,
+			etf_replica_method: None,
+			etf_idx_market: None,
+			etf_idx_asset: None,
+			etf_op_company: None,
+			etf_fee_rate: None,
+			etf_tax_type: None,
+		});
+	}
+	Ok(items)
+}
+
+/// `ETF 전종목 기본정보` CSV 를 읽는다
+pub fn load_etf_master<P: AsRef<Path>>(path: P, info_date: NaiveDate) -> Result<Vec<ProductItem>, Error> {
+	let text = read_csv_text(path)?;
+	let mut reader = csv::Reader::from_reader(text.as_bytes());
+
+	let mut items = Vec::<ProductItem>::new();
+	for row in reader.deserialize::<EtfRow>() {
+		let row = row?;
+		items.push(ProductItem {
+			code: row.code.trim().to_string(),
+			info_date,
+			name: row.name.trim().to_string(),
+			market: Market::ETF,
+			std_code: non_empty(&row.std_code),
+			list_date: parse_date(&row.list_date),
+			kind: None,
+			secu_group: None,
+			sect: None,
+			par: None,
+			list_shares: parse_number(&row.list_shares),
+			etf_obj_idx: non_empty(&row.obj_idx),
+			etf_idx_inst: non_empty(&row.idx_inst),
+			etf_idx_multiplier: parse_multiplier(&row.idx_multiplier),
+			etf_replica_method: non_empty(&row.replica_method),
+			etf_idx_market: non_empty(&row.idx_market),
+			etf_idx_asset: non_empty(&row.idx_asset),
+			etf_op_company: non_empty(&row.op_company),
+			etf_fee_rate: parse_number(&row.fee_rate),
+			etf_tax_type: non_empty(&row.tax_type),
+		});
+	}
+	Ok(items)
+}
+
+/// KRX 에서 내려받은 CSV 는 EUC-KR 이다. UTF-8 로 변환해서 저장한 파일도 받아들인다.
+fn read_csv_text<P: AsRef<Path>>(path: P) -> Result<String, Error> {
+	let bytes = std::fs::read(path)?;
+	match String::from_utf8(bytes) {
+		Ok(text) => Ok(text.trim_start_matches('\u{feff}').to_string()),
+		Err(err) => {
+			let (text, _, had_errors) = encoding_rs::EUC_KR.decode(err.as_bytes());
+			if had_errors {
+				return Err("CSV file is neither UTF-8 nor EUC-KR".into());
+			}
+			Ok(text.into_owned())
+		}
+	}
+}
+
+fn non_empty(text: &str) -> Option<String> {
+	let text = text.trim();
+	if text.is_empty() || text == "-" {
+		None
+	} else {
+		Some(text.to_string())
+	}
+}
+
+fn parse_date(text: &str) -> Option<NaiveDate> {
+	let text = text.trim();
+	["%Y/%m/%d", "%Y-%m-%d", "%Y%m%d"].iter()
+		.find_map(|fmt| NaiveDate::parse_from_str(text, fmt).ok())
+}
+
+fn parse_number<T: std::str::FromStr>(text: &str) -> Option<T> {
+	text.trim().replace(",", "").parse::<T>().ok()
+}
+
+/// `일반 (1)`, `레버리지 (2)`, `인버스 2X (-2)` 형태에서 괄호 안의 배수만 꺼낸다
+fn parse_multiplier(text: &str) -> Option<i32> {
+	let text = text.trim();
+	let inner = match (text.rfind('('), text.rfind(')')) {
+		(Some(start), Some(end)) if start < end => &text[start + 1..end],
+		_ => text,
+	};
+	inner.trim().parse::<i32>().ok()
+}
@@ -1,3 +1,77 @@
+mod archive;
+mod http;
 mod naver;
+mod dart;
+mod krx;
 
-pub use naver::*;
+use std::{env, sync::Arc};
+
+use async_trait::async_trait;
+
+pub use archive::{ArchiveConfig, ArchiveEntry, ResponseArchive};
+pub use http::{HttpClient, HttpConfig, HttpError};
+pub use naver::{NaverDataSource, WisereportDataSource};
+pub use dart::{CorpCodes, DartConfig, DartDataSource};
+pub use krx::{load_etf_master, load_stock_master};
+
+use crate::{entities::FiscalMonth, fi_registry::Financials, types::Error};
+
+#[async_trait]
+pub trait FinancialDataSource: Send + Sync {
+	fn name(&self) -> &'static str;
+	async fn query_stock_financials(&self, stock_code: &str) -> Result<Financials, Error>;
+}
+
+#[derive(Debug, Clone)]
+pub struct DataSourceConfig {
+	pub source: String,
+	pub http: HttpConfig,
+	pub dart: Option<DartConfig>,
+	pub archive: ArchiveConfig,
+}
+
+impl DataSourceConfig {
+	pub fn from_env() -> Self {
+		let source = env::var("DATA_SOURCE")
+			.unwrap_or_else(|_| NaverDataSource::NAME.to_string());
+		let http = HttpConfig::from_env();
+		let dart = DartConfig::from_env();
+		let archive = ArchiveConfig::from_env();
+		Self {
+			source,
+			http,
+			dart,
+			archive,
+		}
+	}
+}
+
+/// `fiscal_months`: 저장된 종목별 결산월. DART 보고서의 기간을 정할 때 쓴다
+pub fn create(conf: &DataSourceConfig, fiscal_months: &[FiscalMonth]) -> Result<Box<dyn FinancialDataSource>, Error> {
+	let http = Arc::new(HttpClient::new(&conf.http)?);
+	let source: Box<dyn FinancialDataSource> = match conf.source.as_str() {
+		NaverDataSource::NAME => {
+			let naver = NaverDataSource::new(http);
+			match conf.archive.dir.as_ref() {
+				Some(dir) => Box::new(naver.with_archive(Arc::new(ResponseArchive::open(dir)?))),
+				None => Box::new(naver),
+			}
+		}
+		WisereportDataSource::NAME => {
+			let wisereport = WisereportDataSource::new(http);
+			match conf.archive.dir.as_ref() {
+				Some(dir) => Box::new(wisereport.with_archive(Arc::new(ResponseArchive::open(dir)?))),
+				None => Box::new(wisereport),
+			}
+		}
+		DartDataSource::NAME => {
+			let Some(dart_conf) = conf.dart.as_ref() else {
+				return Err("DART_API_KEY must be set to use the DART data source".into());
+			};
+			Box::new(DartDataSource::new(dart_conf, http)?.with_fiscal_months(fiscal_months))
+		}
+		_ => return Err(format!("Unknown data source `{}`", conf.source).into()),
+	};
+	log::info!("Using data source: {}", source.name());
+	Ok(source)
+}
@@ -1,46 +1,62 @@
-use std::{collections::HashMap, ops::Range, time::Duration};
+mod price;
+mod wisereport;
 
-use ratelimit::Ratelimiter;
-use reqwest::IntoUrl;
+use std::{collections::{HashMap, HashSet}, ops::Range, sync::Arc};
+
+use async_trait::async_trait;
+use chrono::Local;
 use scraper::{Element, ElementRef, Html, Selector};
 
-use crate::{entities::FinancialInfo, fi_registry::Financials, types::{Error, YearMonth}};
+pub use wisereport::WisereportDataSource;
 
-lazy_static::lazy_static! {
-	static ref NAVER_RATELIMITER: Ratelimiter = Ratelimiter::builder(1, std::time::Duration::from_millis(500))
-		.initial_available(1)
-		.build()
-		.unwrap();
+use crate::{data_source::{ArchiveEntry, FinancialDataSource, HttpClient, ResponseArchive, archive::archive_response}, fi_registry::{Financials, FinancialInfoRegistry}, types::{Amount, Error, StatementBasis, Unit, YearMonth}};
+
+#[derive(Clone)]
+pub struct NaverDataSource {
+	http: Arc<HttpClient>,
+	archive: Option<Arc<ResponseArchive>>,
 }
 
-async fn request<U: IntoUrl>(url: U) -> Result<String, Error> {
-	while let Err(dur) = NAVER_RATELIMITER.try_wait() {
-		tokio::time::sleep(dur).await;
+impl NaverDataSource {
+	pub const NAME: &'static str = "naver";
+
+	pub fn new(http: Arc<HttpClient>) -> Self {
+		Self { http, archive: None }
 	}
 
-	let client = reqwest::Client::new();
-	let resp = client.get(url)
-		.header("Content-Type", "application/x-www-form-urlencoded")
-		.timeout(Duration::from_secs(5))
-		.send()
-		.await
-		.map_err(|e| e.to_string())?;
-
-	let text = resp.text()
-		.await
-		.map_err(|e| e.to_string())?;
-	Ok(text)
+	/// 가져온 페이지를 아카이브에 보관한다
+	pub fn with_archive(mut self, archive: Arc<ResponseArchive>) -> Self {
+		self.archive = Some(archive);
+		self
+	}
+
+	/// 보관된 페이지를 네트워크 없이 다시 파싱한다. 수집시각은 페이지를 가져온 시각
+	pub async fn parse_archived(archive: &ResponseArchive, entry: &ArchiveEntry) -> Result<Financials, Error> {
+		let text = archive.load(entry).await?;
+		let mut financials = parse_html_resp(&text, &entry.stock_code)?;
+		financials.fetched_at = entry.fetched_at.naive_local();
+		financials.remove_duplicate();
+		Ok(financials)
+	}
 }
 
+#[async_trait]
+impl FinancialDataSource for NaverDataSource {
+	fn name(&self) -> &'static str {
+		Self::NAME
+	}
 
-pub async fn query_stock_financials(stock_code: &str) -> Result<Financials, Error> {
-	let params = [("code", stock_code)];
-	let url = reqwest::Url::parse_with_params("https://finance.naver.com/item/main.naver", params)
-		.map_err(|e| e.to_string())?;
-	let text = request(url).await?;
-	let mut financials = parse_html_resp(&text, stock_code)?;
-	financials.remove_duplicate();
-	Ok(financials)
+	async fn query_stock_financials(&self, stock_code: &str) -> Result<Financials, Error> {
+		let params = [("code", stock_code)];
+		let url = reqwest::Url::parse_with_params("https://finance.naver.com/item/main.naver", params)
+			.map_err(|e| e.to_string())?;
+		let fetched_at = Local::now();
+		let text = self.http.get_text(url).await?;
+		archive_response(self.archive.as_deref(), Self::NAME, stock_code, fetched_at, &text).await;
+		let mut financials = parse_html_resp(&text, stock_code)?;
+		financials.remove_duplicate();
+		Ok(financials)
+	}
 }
 
 lazy_static::lazy_static! {
@@ -117,6 +133,8 @@ fn parse_html_resp(html: &str, stock_code: &str) -> Result<Financials, Error> {
 
 	let mut annual_columns = Vec::<(YearMonth, usize)>::new();
 	let mut quarter_columns = Vec::<(YearMonth, usize)>::new();
+	let mut annual_estimate_columns = Vec::<(YearMonth, usize)>::new();
+	let mut quarter_estimate_columns = Vec::<(YearMonth, usize)>::new();
 
 	let mut ths = tr.select(&TH_SELECTOR);
 	let mut col_idx = 0;
@@ -141,25 +159,63 @@ fn parse_html_resp(html: &str, stock_code: &str) -> Result<Financials, Error> {
 			.unwrap_or(1);
 
 		let text = get_text(th);
-		if text.is_empty() || text.ends_with("(E)") {
+		if text.is_empty() {
 			col_idx += colspan;
 			continue;
 		}
 
+		// 컨센서스 추정치 컬럼은 `2025.12(E)` 형태로 표시된다
+		let estimate = text.strip_suffix("(E)").map(|t| t.trim());
 		if annual_col_indices.contains(&col_idx) {
-			let year_month: YearMonth = text.parse()?;
-			annual_columns.push((year_month, col_idx));
+			match estimate {
+				Some(text) => annual_estimate_columns.push((text.parse()?, col_idx)),
+				None => annual_columns.push((text.parse()?, col_idx)),
+			}
 		}
 		if quater_col_indices.contains(&col_idx) {
-			let year_month: YearMonth = text.parse()?;
-			quarter_columns.push((year_month, col_idx));
+			match estimate {
+				Some(text) => quarter_estimate_columns.push((text.parse()?, col_idx)),
+				None => quarter_columns.push((text.parse()?, col_idx)),
+			}
 		}
 		col_idx += colspan;
 	}
 
+	// 세번째 헤더 행은 컬럼마다 `IFRS연결`, `IFRS별도` 처럼 재무제표 구분을 표시한다
+	let mut column_bases = HashMap::<usize, StatementBasis>::new();
+	if let Some(tr) = thead_trs.next() {
+		let mut ths = tr.select(&TH_SELECTOR);
+		let mut col_idx = 0;
+		loop {
+			if let Some(rowspan) = rowspans.get(&col_idx) {
+				let new_rowspan = rowspan -1;
+				if new_rowspan > 1 {
+					rowspans.insert(col_idx, new_rowspan);
+				} else {
+					rowspans.remove(&col_idx);
+				}
+				col_idx += 1;
+				continue
+			}
+
+			let Some(th) = ths.next() else {
+				break
+			};
+
+			let colspan = th.attr("colspan")
+				.map(|colspan| colspan.parse::<usize>().unwrap_or(1))
+				.unwrap_or(1);
+
+			if let Some(basis) = parse_basis(&get_text(th)) {
+				column_bases.insert(col_idx, basis);
+			}
+			col_idx += colspan;
+		}
+	}
+
 	// println!("Annual columns: {:?}", annual_columns);
 	// println!("Quarter columns: {:?}", quarter_columns);
-	let mut financials = Financials::new(stock_code);
+	let mut financials = Financials::new(NaverDataSource::NAME, stock_code);
 
 	let mut tbody_trs = table.select(&TBODY_TR_SELECTOR);
 	loop {
@@ -189,7 +245,7 @@ fn parse_html_resp(html: &str, stock_code: &str) -> Result<Financials, Error> {
 			};
 
 			if col_idx == 0 {
-				if text == "매출액" || text == "영업이익" || text == "당기순이익" || text == "주당배당금(원)" || text == "시가배당률(%)" {
+				if FinancialInfoRegistry::is_fi_property(&text) {
 					data_name = Some(text);
 				}
 				col_idx += colspan;
@@ -198,19 +254,35 @@ fn parse_html_resp(html: &str, stock_code: &str) -> Result<Financials, Error> {
 
 			let annual_column = annual_columns.iter().find(|(_, ci)| *ci == col_idx);
 			if let Some((year_month, _)) = annual_column {
-				let value = text.replace(",", "").parse::<f32>().ok();
+				let value = text.replace(",", "").parse::<Amount>().ok();
 				// println!("Annual {year_month} {data_name:?} {text} ({value:?})");
 				if let Some(data_name) = data_name.as_ref() && value.is_some() {
-					financials.annuals.register(*year_month, data_name, value);
+					financials.annuals.register(*year_month, data_name, value, naver_unit(data_name));
 				};
 			}
 
 			let quarter_column = quarter_columns.iter().find(|(_, ci)| *ci == col_idx);
 			if let Some((year_month, _)) = quarter_column {
-				let value = text.replace(",", "").parse::<f32>().ok();
+				let value = text.replace(",", "").parse::<Amount>().ok();
 				// println!("Quarter {year_month} {data_name:?} {text} ({value:?})");
 				if let Some(data_name) = data_name.as_ref() && value.is_some() {
-					financials.quarters.register(*year_month, data_name, value);
+					financials.quarters.register(*year_month, data_name, value, naver_unit(data_name));
+				};
+			}
+
+			let annual_estimate_column = annual_estimate_columns.iter().find(|(_, ci)| *ci == col_idx);
+			if let Some((year_month, _)) = annual_estimate_column {
+				let value = text.replace(",", "").parse::<Amount>().ok();
+				if let Some(data_name) = data_name.as_ref() && value.is_some() {
+					financials.annual_estimates.register(*year_month, data_name, value, naver_unit(data_name));
+				};
+			}
+
+			let quarter_estimate_column = quarter_estimate_columns.iter().find(|(_, ci)| *ci == col_idx);
+			if let Some((year_month, _)) = quarter_estimate_column {
+				let value = text.replace(",", "").parse::<Amount>().ok();
+				if let Some(data_name) = data_name.as_ref() && value.is_some() {
+					financials.quarter_estimates.register(*year_month, data_name, value, naver_unit(data_name));
 				};
 			}
 
@@ -218,9 +290,29 @@ fn parse_html_resp(html: &str, stock_code: &str) -> Result<Financials, Error> {
 		}
 	}
 
+	for (registry, columns) in [(&mut financials.annuals, &annual_columns), (&mut financials.quarters, &quarter_columns)] {
+		for (year_month, col_idx) in columns.iter() {
+			match column_bases.get(col_idx) {
+				Some(basis) => registry.set_basis(*year_month, *basis),
+				None => log::debug!("Unknown statement basis of {year_month} for stock `{stock_code}`"),
+			}
+		}
+	}
+
 	Ok(financials)
 }
 
+/// `IFRS연결`, `GAAP개별` 같은 헤더에서 재무제표 구분을 찾는다
+fn parse_basis(text: &str) -> Option<StatementBasis> {
+	if text.contains("연결") {
+		Some(StatementBasis::Consolidated)
+	} else if text.contains("별도") || text.contains("개별") {
+		Some(StatementBasis::Separate)
+	} else {
+		None
+	}
+}
+
 fn get_text(el: ElementRef<'_>) -> String {
 	let mut string = String::new();
 
@@ -250,16 +342,84 @@ fn get_text_opt(el: ElementRef<'_>) -> Option<String> {
 	}
 }
 
+/// 네이버 재무 표의 항목별 단위
+fn naver_unit(data_name: &str) -> Unit {
+	match data_name {
+		"매출액" | "영업이익" | "당기순이익" => Unit::HundredMillionWon,
+		"EPS(원)" | "BPS(원)" | "주당배당금(원)" => Unit::Won,
+		"PER(배)" | "PBR(배)" => Unit::Multiple,
+		_ => Unit::Percent,
+	}
+}
+
 impl Financials {
+	/// 같은 년월이 두 번 나오면 앞의 것만 남긴다.
+	///
+	/// 결산월을 바꾼 해(예: 2023.03 → 2023.12)의 두 연간 실적은 서로 다른 회계연도이므로 모두 남긴다.
 	pub fn remove_duplicate(&mut self) {
-		let dup = self.annuals.list.clone();
-		for annual in dup.into_iter() {
-			if let Some((i, fi)) = self.annuals.list.iter().enumerate().find(|(_, fi)| fi.year_month.year == annual.year_month.year) {
-				// drops the older one
-				if fi.year_month < annual.year_month {
-					self.annuals.list.remove(i);
-				}
-			}
-		}
+		let mut seen = HashSet::<YearMonth>::new();
+		self.annuals.list.retain(|fi| seen.insert(fi.year_month));
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use crate::entities::FinancialInfo;
+
+	use super::*;
+
+	const MAIN_PAGE: &str = include_str!(concat!(env!("CARGO_MANIFEST_DIR"), "/tests/fixtures/naver/main_005930.html"));
+
+	fn amount(s: &str) -> Option<Amount> {
+		Some(s.parse().unwrap())
+	}
+
+	fn find(registry: &FinancialInfoRegistry, year_month: &str) -> FinancialInfo {
+		let year_month: YearMonth = year_month.parse().unwrap();
+		registry.iter().find(|fi| fi.year_month == year_month).cloned().unwrap()
+	}
+
+	#[test]
+	fn parse_main_page() {
+		let financials = parse_html_resp(MAIN_PAGE, "005930").unwrap();
+		assert_eq!(financials.annuals.len(), 3);
+		assert_eq!(financials.quarters.len(), 5);
+		assert_eq!(financials.annual_estimates.len(), 1);
+		assert_eq!(financials.quarter_estimates.len(), 1);
+
+		let annual = find(&financials.annuals, "2023.12");
+		assert_eq!(annual.basis, StatementBasis::Consolidated);
+		assert_eq!(annual.sales, amount("2589355"));
+		assert_eq!(annual.profit, amount("65670"));
+		assert_eq!(annual.roe, amount("4.15"));
+		assert_eq!(annual.reserve_ratio, amount("39114.28"));
+		assert_eq!(annual.eps, amount("2131"));
+		assert_eq!(annual.dividend, amount("1444"));
+
+		// 빈 칸은 값이 없다
+		let quarter = find(&financials.quarters, "2024.06");
+		assert_eq!(quarter.net_income, amount("98413"));
+		assert_eq!(quarter.dividend, None);
+
+		let estimate = find(&financials.annual_estimates, "2024.12");
+		assert_eq!(estimate.sales, amount("3094800"));
+		assert_eq!(estimate.debt_ratio, None);
+	}
+
+	#[tokio::test]
+	async fn reparse_archived_page() {
+		let dir = std::env::temp_dir().join(format!("stock-fn-archive-test-{}", std::process::id()));
+		let archive = ResponseArchive::open(&dir).unwrap();
+		let fetched_at = "2024-08-01T09:00:00+09:00".parse::<chrono::DateTime<chrono::FixedOffset>>().unwrap().with_timezone(&Local);
+		archive.store(NaverDataSource::NAME, "005930", fetched_at, MAIN_PAGE).await.unwrap();
+
+		let entries = archive.list(NaverDataSource::NAME, "005930").await.unwrap();
+		assert_eq!(entries.len(), 1);
+		let financials = NaverDataSource::parse_archived(&archive, &entries[0]).await.unwrap();
+		std::fs::remove_dir_all(&dir).ok();
+
+		assert_eq!(financials.fetched_at, fetched_at.naive_local());
+		assert_eq!(financials.annuals.len(), 3);
+		assert_eq!(find(&financials.quarters, "2024.03").sales, amount("719156"));
 	}
 }
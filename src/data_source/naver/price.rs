@@ -0,0 +1,153 @@
+use chrono::{Local, NaiveDate};
+use serde::Deserialize;
+
+use crate::{data_source::{ArchiveEntry, NaverDataSource, ResponseArchive, archive::archive_response}, entities::StockPrice, types::Error};
+
+#[derive(Deserialize)]
+struct ChartProtocol {
+	chartdata: ChartData,
+}
+
+#[derive(Deserialize)]
+struct ChartData {
+	#[serde(default)]
+	item: Vec<ChartItem>,
+}
+
+#[derive(Deserialize)]
+struct ChartItem {
+	/// `일자|시가|고가|저가|종가|거래량`
+	#[serde(rename = "@data")]
+	data: String,
+}
+
+impl NaverDataSource {
+	/// 일봉 데이터를 보관할 때의 소스 이름
+	pub const CHART_ARCHIVE: &'static str = "naver.fchart";
+
+	/// 네이버 fchart 의 일봉 데이터를 오래된 순서로 조회한다.
+	///
+	/// fchart 는 거래대금을 제공하지 않으므로 `trading_value` 는 비워둔다.
+	/// 첫 번째 항목의 `diff` 는 이전 종가를 알 수 없으므로 `None` 이다.
+	pub async fn query_daily_prices(&self, stock_code: &str, count: usize) -> Result<Vec<StockPrice>, Error> {
+		let count = count.to_string();
+		let params = [("symbol", stock_code), ("timeframe", "day"), ("count", count.as_str()), ("requestType", "0")];
+		let url = reqwest::Url::parse_with_params("https://fchart.stock.naver.com/sise.nhn", params)
+			.map_err(|e| e.to_string())?;
+		let fetched_at = Local::now();
+		let text = self.http.get_text(url).await?;
+		archive_response(self.archive.as_deref(), Self::CHART_ARCHIVE, stock_code, fetched_at, &text).await;
+		parse_chart_resp(&text, stock_code)
+	}
+
+	/// 보관된 일봉 데이터를 네트워크 없이 다시 파싱한다
+	pub async fn parse_archived_prices(archive: &ResponseArchive, entry: &ArchiveEntry) -> Result<Vec<StockPrice>, Error> {
+		let text = archive.load(entry).await?;
+		parse_chart_resp(&text, &entry.stock_code)
+	}
+}
+
+fn parse_chart_resp(xml: &str, stock_code: &str) -> Result<Vec<StockPrice>, Error> {
+	let protocol: ChartProtocol = quick_xml::de::from_str(xml)?;
+
+	let mut prices = Vec::<StockPrice>::with_capacity(protocol.chartdata.item.len());
+	let mut prev_closing: Option<u32> = None;
+	for item in protocol.chartdata.item.iter() {
+		let fields: Vec<&str> = item.data.split('|').collect();
+		if fields.len() < 6 {
+			return Err(format!("Invalid chart item `{}`", item.data).into());
+		}
+
+		let ord_date = NaiveDate::parse_from_str(fields[0], "%Y%m%d")?;
+		let opening = fields[1].parse::<u32>().ok();
+		let highest = fields[2].parse::<u32>().ok();
+		let lowest = fields[3].parse::<u32>().ok();
+		let closing = fields[4].parse::<u32>().ok();
+		let volume = fields[5].parse::<u64>().ok();
+		let diff = closing.zip(prev_closing).map(|(closing, prev)| closing as i32 - prev as i32);
+
+		prices.push(StockPrice {
+			stock_code: stock_code.to_string(),
+			ord_date,
+			opening,
+			highest,
+			lowest,
+			closing,
+			diff,
+			volume,
+			trading_value: None,
+		});
+		prev_closing = closing;
+	}
+	Ok(prices)
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	const CHART: &str = include_str!(concat!(env!("CARGO_MANIFEST_DIR"), "/tests/fixtures/naver/fchart_005930.xml"));
+
+	#[test]
+	fn parse_daily_prices() {
+		let prices = parse_chart_resp(CHART, "005930").unwrap();
+		assert_eq!(prices.len(), 5);
+
+		let first = &prices[0];
+		assert_eq!(first.stock_code, "005930");
+		assert_eq!(first.ord_date, NaiveDate::from_ymd_opt(2024, 7, 22).unwrap());
+		assert_eq!((first.opening, first.highest, first.lowest, first.closing), (Some(80800), Some(81000), Some(79800), Some(79900)));
+		assert_eq!(first.volume, Some(16561738));
+		assert_eq!(first.trading_value, None);
+		// 이전 종가를 알 수 없다
+		assert_eq!(first.diff, None);
+
+		// 전일 종가 대비
+		assert_eq!(prices[1].diff, Some(500));
+		assert_eq!(prices[3].diff, Some(-300));
+		assert_eq!(prices[4].ord_date, NaiveDate::from_ymd_opt(2024, 7, 26).unwrap());
+		assert_eq!(prices[4].diff, Some(500));
+	}
+
+	#[test]
+	fn diff_without_previous_closing() {
+		let xml = r#"<protocol><chartdata symbol="005930">
+			<item data="20240722|80800|81000|79800|null|0" />
+			<item data="20240723|80500|81300|80300|80400|13917413" />
+			<item data="20240724|81200|81300|80100|81000|14376582" />
+		</chartdata></protocol>"#;
+		let prices = parse_chart_resp(xml, "005930").unwrap();
+		assert_eq!(prices[0].closing, None);
+		assert_eq!(prices[1].diff, None);
+		assert_eq!(prices[2].diff, Some(600));
+	}
+
+	#[test]
+	fn malformed_item() {
+		let xml = r#"<protocol><chartdata symbol="005930">
+			<item data="20240722|80800|81000|79800|79900|16561738" />
+			<item data="20240723|80500|81300|80300" />
+		</chartdata></protocol>"#;
+		assert!(parse_chart_resp(xml, "005930").is_err());
+	}
+
+	#[tokio::test]
+	async fn reparse_archived_prices() {
+		let dir = std::env::temp_dir().join(format!("stock-fn-chart-archive-test-{}", std::process::id()));
+		let archive = ResponseArchive::open(&dir).unwrap();
+		archive.store(NaverDataSource::CHART_ARCHIVE, "005930", Local::now(), CHART).await.unwrap();
+
+		let entries = archive.list(NaverDataSource::CHART_ARCHIVE, "005930").await.unwrap();
+		let prices = NaverDataSource::parse_archived_prices(&archive, &entries[0]).await.unwrap();
+		std::fs::remove_dir_all(&dir).ok();
+
+		assert_eq!(prices.len(), 5);
+		assert_eq!(prices[4].closing, Some(81200));
+	}
+
+	#[test]
+	fn empty_chartdata() {
+		let xml = r#"<protocol><chartdata symbol="000000" name="" count="0" timeframe="day"></chartdata></protocol>"#;
+		assert!(parse_chart_resp(xml, "000000").unwrap().is_empty());
+	}
+}
@@ -0,0 +1,320 @@
+use std::sync::Arc;
+
+use async_trait::async_trait;
+use chrono::Local;
+use scraper::{ElementRef, Html, Selector};
+
+use crate::{data_source::{ArchiveEntry, FinancialDataSource, HttpClient, ResponseArchive, archive::archive_response}, fi_registry::{FinancialInfoRegistry, Financials}, types::{Amount, Error, YearMonth}};
+
+use super::{get_text, naver_unit, parse_basis};
+
+/// 네이버 증권 기업정보(wisereport)의 재무요약. 기업실적분석보다 긴 기간(약 10년)을 제공해서 과거 실적을 채우는 데 쓴다
+#[derive(Clone)]
+pub struct WisereportDataSource {
+	http: Arc<HttpClient>,
+	archive: Option<Arc<ResponseArchive>>,
+}
+
+impl WisereportDataSource {
+	pub const NAME: &'static str = "wisereport";
+
+	/// 다시 파싱할 수 있는 재무요약(연간, 분기) 페이지의 보관 소스 이름.
+	/// 기업현황(`c1010001`) 페이지는 요청 값만 담고 있어서 다시 파싱하지 않는다
+	pub const SUMMARY_ARCHIVES: [&'static str; 2] = ["wisereport.cF1001_Y", "wisereport.cF1001_Q"];
+
+	const BASE_URL: &'static str = "https://navercomp.wisereport.co.kr/v2/company";
+
+	pub fn new(http: Arc<HttpClient>) -> Self {
+		Self { http, archive: None }
+	}
+
+	/// 가져온 페이지를 `wisereport.{페이지}` 소스 이름으로 아카이브에 보관한다
+	pub fn with_archive(mut self, archive: Arc<ResponseArchive>) -> Self {
+		self.archive = Some(archive);
+		self
+	}
+
+	/// 보관된 재무요약 페이지를 네트워크 없이 다시 파싱한다. 연간 페이지는 `annuals`, 분기 페이지는 `quarters` 만 채운다
+	pub async fn parse_archived(archive: &ResponseArchive, entry: &ArchiveEntry) -> Result<Financials, Error> {
+		let mut financials = Financials::new(Self::NAME, &entry.stock_code);
+		financials.backfill = true;
+		financials.fetched_at = entry.fetched_at.naive_local();
+
+		let registry = match entry.source.as_str() {
+			source if source == Self::SUMMARY_ARCHIVES[0] => &mut financials.annuals,
+			source if source == Self::SUMMARY_ARCHIVES[1] => &mut financials.quarters,
+			source => return Err(format!("Archived page `{source}` can't be re-parsed").into()),
+		};
+		let text = archive.load(entry).await?;
+		parse_summary(&text, registry)?;
+		Ok(financials)
+	}
+
+	async fn get_text(&self, url: reqwest::Url, page: &str, stock_code: &str) -> Result<String, Error> {
+		let fetched_at = Local::now();
+		let text = self.http.get_text(url).await?;
+		let source = format!("{}.{}", Self::NAME, page);
+		archive_response(self.archive.as_deref(), &source, stock_code, fetched_at, &text).await;
+		Ok(text)
+	}
+
+	/// 재무요약 요청에 필요한 `encparam`, `id` 값을 기업현황 페이지에서 꺼낸다
+	async fn query_params(&self, stock_code: &str) -> Result<(String, Option<String>), Error> {
+		let url = reqwest::Url::parse_with_params(&format!("{}/c1010001.aspx", Self::BASE_URL), [("cmp_cd", stock_code)])
+			.map_err(|e| e.to_string())?;
+		let text = self.get_text(url, "c1010001", stock_code).await?;
+		let Some(encparam) = find_script_value(&text, "encparam") else {
+			return Err(format!("encparam not found for stock `{stock_code}`").into());
+		};
+		let id = find_script_value(&text, "id");
+		Ok((encparam.to_string(), id.map(str::to_string)))
+	}
+
+	/// `freq_typ`: `Y` 연간, `Q` 분기
+	async fn query_summary(&self, stock_code: &str, freq_typ: &str, encparam: &str, id: Option<&str>) -> Result<String, Error> {
+		let mut params = vec![
+			("cmp_cd", stock_code),
+			// 주재무제표
+			("fin_typ", "0"),
+			("freq_typ", freq_typ),
+			("encparam", encparam),
+		];
+		if let Some(id) = id {
+			params.push(("id", id));
+		}
+		let url = reqwest::Url::parse_with_params(&format!("{}/ajax/cF1001.aspx", Self::BASE_URL), params)
+			.map_err(|e| e.to_string())?;
+		self.get_text(url, &format!("cF1001_{freq_typ}"), stock_code).await
+	}
+}
+
+#[async_trait]
+impl FinancialDataSource for WisereportDataSource {
+	fn name(&self) -> &'static str {
+		Self::NAME
+	}
+
+	async fn query_stock_financials(&self, stock_code: &str) -> Result<Financials, Error> {
+		let (encparam, id) = self.query_params(stock_code).await?;
+		let mut financials = Financials::new(Self::NAME, stock_code);
+		// 기업실적분석 페이지에서 수집한 최근 값을 덮어쓰지 않는다
+		financials.backfill = true;
+
+		let annual_html = self.query_summary(stock_code, "Y", &encparam, id.as_deref()).await?;
+		parse_summary(&annual_html, &mut financials.annuals)?;
+		let quarter_html = self.query_summary(stock_code, "Q", &encparam, id.as_deref()).await?;
+		parse_summary(&quarter_html, &mut financials.quarters)?;
+		Ok(financials)
+	}
+}
+
+lazy_static::lazy_static! {
+	static ref TABLE_SELECTOR: Selector = Selector::parse("table").unwrap();
+	static ref THEAD_TH_SELECTOR: Selector = Selector::parse("thead th").unwrap();
+	static ref TBODY_TR_SELECTOR: Selector = Selector::parse("tbody tr").unwrap();
+	static ref TH_SELECTOR: Selector = Selector::parse("th").unwrap();
+	static ref TD_SELECTOR: Selector = Selector::parse("td").unwrap();
+}
+
+/// 재무요약 표를 파싱한다. 추정치(E) 컬럼은 건너뛴다
+fn parse_summary(html: &str, registry: &mut FinancialInfoRegistry) -> Result<(), Error> {
+	let document = Html::parse_fragment(html);
+	let table = document.select(&TABLE_SELECTOR)
+		.find(|table| table.select(&THEAD_TH_SELECTOR).any(|th| parse_period(&get_text(th)).is_some()));
+	let Some(table) = table else {
+		return Err("재무요약 테이블을 찾을 수 없습니다".into());
+	};
+
+	// 데이터 컬럼 순서대로의 (년월, 추정치 여부, 헤더)
+	let columns: Vec<(YearMonth, bool, String)> = table.select(&THEAD_TH_SELECTOR)
+		.map(get_text)
+		.filter_map(|text| parse_period(&text).map(|(ym, estimate)| (ym, estimate, text)))
+		.collect();
+
+	for tr in table.select(&TBODY_TR_SELECTOR) {
+		let Some(th) = tr.select(&TH_SELECTOR).next() else {
+			continue
+		};
+		let Some(data_name) = naver_data_name(&get_text(th)) else {
+			continue
+		};
+		for (td, (year_month, estimate, _)) in tr.select(&TD_SELECTOR).zip(columns.iter()) {
+			if *estimate {
+				continue
+			}
+			if let Some(value) = parse_value(td) {
+				registry.register(*year_month, data_name, Some(value), naver_unit(data_name));
+			}
+		}
+	}
+
+	for (year_month, estimate, header) in columns.iter() {
+		if let (false, Some(basis)) = (*estimate, parse_basis(header)) {
+			registry.set_basis(*year_month, basis);
+		}
+	}
+	Ok(())
+}
+
+/// `2023/12 (IFRS연결)`, `2024/12(E) (IFRS연결)` 형태의 헤더에서 년월과 추정치 여부를 꺼낸다
+fn parse_period(text: &str) -> Option<(YearMonth, bool)> {
+	let period = text.split(['(', ' ']).next()?.trim();
+	let year_month = period.replace('/', ".").parse::<YearMonth>().ok()?;
+	Some((year_month, text.contains("(E)")))
+}
+
+/// 반올림되지 않은 값이 `title` 속성에 있으면 그것을 쓴다
+fn parse_value(td: ElementRef<'_>) -> Option<Amount> {
+	let text = td.attr("title")
+		.map(str::to_string)
+		.unwrap_or_else(|| get_text(td));
+	text.replace(",", "").trim().parse::<Amount>().ok()
+}
+
+/// 재무요약의 행 이름을 기업실적분석의 항목 이름으로 바꾼다
+fn naver_data_name(row_name: &str) -> Option<&'static str> {
+	let data_name = match row_name {
+		"매출액" => "매출액",
+		"영업이익" => "영업이익",
+		"당기순이익" => "당기순이익",
+		"영업이익률" => "영업이익률",
+		"순이익률" => "순이익률",
+		"ROE(%)" => "ROE(지배주주)",
+		"부채비율" => "부채비율",
+		"자본유보율" => "유보율",
+		"EPS(원)" => "EPS(원)",
+		"PER(배)" => "PER(배)",
+		"BPS(원)" => "BPS(원)",
+		"PBR(배)" => "PBR(배)",
+		"현금DPS(원)" => "주당배당금(원)",
+		"현금배당수익률" => "시가배당률(%)",
+		_ => return None,
+	};
+	Some(data_name)
+}
+
+/// 스크립트의 `key: 'value'` 에서 값을 꺼낸다. `gid` 처럼 `key` 로 끝나는 다른 이름은 건너뛴다
+fn find_script_value<'a>(html: &'a str, key: &str) -> Option<&'a str> {
+	html.match_indices(key)
+		.filter(|(i, _)| !html[..*i].chars().next_back().is_some_and(|c| c.is_alphanumeric() || c == '_'))
+		.find_map(|(i, _)| {
+			let value = html[i + key.len()..].trim_start().strip_prefix(':')?.trim_start().strip_prefix('\'')?;
+			let len = value.find('\'')?;
+			Some(&value[..len])
+		})
+}
+
+#[cfg(test)]
+mod tests {
+	use crate::{entities::FinancialInfo, types::StatementBasis};
+
+	use super::*;
+
+	macro_rules! fixture {
+		($name:literal) => {
+			include_str!(concat!(env!("CARGO_MANIFEST_DIR"), "/tests/fixtures/wisereport/", $name))
+		};
+	}
+
+	fn amount(s: &str) -> Option<Amount> {
+		Some(s.parse().unwrap())
+	}
+
+	fn find(registry: &FinancialInfoRegistry, year_month: &str) -> Option<FinancialInfo> {
+		let year_month: YearMonth = year_month.parse().unwrap();
+		registry.iter().find(|fi| fi.year_month == year_month).cloned()
+	}
+
+	#[test]
+	fn parse_annual_summary() {
+		let mut registry = FinancialInfoRegistry::new("005930");
+		parse_summary(fixture!("cF1001_Y_005930.html"), &mut registry).unwrap();
+
+		// 추정치(E) 컬럼은 건너뛴다
+		assert_eq!(registry.len(), 3);
+		assert!(find(&registry, "2024.12").is_none());
+
+		let annual = find(&registry, "2023.12").unwrap();
+		assert_eq!(annual.basis, StatementBasis::Consolidated);
+		// 반올림된 본문 대신 title 의 값
+		assert_eq!(annual.sales, amount("2589354.94"));
+		assert_eq!(annual.profit, amount("65669.76"));
+		assert_eq!(annual.net_income, amount("154871.47"));
+		// 행 이름을 기업실적분석 항목으로 바꾼다
+		assert_eq!(annual.roe, amount("4.15"));
+		assert_eq!(annual.reserve_ratio, amount("39114.28"));
+		assert_eq!(annual.eps, amount("2131"));
+		// title 이 없으면 본문
+		assert_eq!(annual.dividend, amount("1444"));
+		assert_eq!(annual.debt_ratio, None);
+
+		assert_eq!(find(&registry, "2021.12").unwrap().sales, amount("2796048"));
+	}
+
+	#[test]
+	fn parse_separate_quarter_summary() {
+		let mut registry = FinancialInfoRegistry::new("000000");
+		parse_summary(fixture!("cF1001_Q_separate.html"), &mut registry).unwrap();
+
+		assert_eq!(registry.len(), 2);
+		let q1 = find(&registry, "2024.03").unwrap();
+		assert_eq!(q1.basis, StatementBasis::Separate);
+		assert_eq!(q1.sales, amount("512.37"));
+		assert_eq!(q1.profit, amount("-12.40"));
+
+		// 숫자가 아닌 칸은 값이 없다
+		let q2 = find(&registry, "2024.06").unwrap();
+		assert_eq!(q2.basis, StatementBasis::Separate);
+		assert_eq!(q2.sales, None);
+		assert_eq!(q2.profit, amount("8.05"));
+	}
+
+	#[test]
+	fn summary_without_period_table() {
+		let mut registry = FinancialInfoRegistry::new("005930");
+		assert!(parse_summary("<table><thead><tr><th>항목</th></tr></thead></table>", &mut registry).is_err());
+	}
+
+	#[test]
+	fn period_headers() {
+		assert_eq!(parse_period("2023/12 (IFRS연결)"), Some(("2023.12".parse().unwrap(), false)));
+		assert_eq!(parse_period("2024/12(E) (IFRS연결)"), Some(("2024.12".parse().unwrap(), true)));
+		assert_eq!(parse_period("주요재무정보"), None);
+	}
+
+	#[tokio::test]
+	async fn reparse_archived_summary() {
+		let dir = std::env::temp_dir().join(format!("stock-fn-wisereport-archive-test-{}", std::process::id()));
+		let archive = ResponseArchive::open(&dir).unwrap();
+		let fetched_at = "2024-08-01T09:00:00+09:00".parse::<chrono::DateTime<chrono::FixedOffset>>().unwrap().with_timezone(&Local);
+		let [annual_source, quarter_source] = WisereportDataSource::SUMMARY_ARCHIVES;
+		archive.store(annual_source, "005930", fetched_at, fixture!("cF1001_Y_005930.html")).await.unwrap();
+		archive.store(quarter_source, "005930", fetched_at, fixture!("cF1001_Q_separate.html")).await.unwrap();
+		archive.store("wisereport.c1010001", "005930", fetched_at, fixture!("c1010001_005930.html")).await.unwrap();
+
+		let annual_entry = archive.list(annual_source, "005930").await.unwrap().remove(0);
+		let quarter_entry = archive.list(quarter_source, "005930").await.unwrap().remove(0);
+		let params_entry = archive.list("wisereport.c1010001", "005930").await.unwrap().remove(0);
+		let annual = WisereportDataSource::parse_archived(&archive, &annual_entry).await.unwrap();
+		let quarter = WisereportDataSource::parse_archived(&archive, &quarter_entry).await.unwrap();
+		let params = WisereportDataSource::parse_archived(&archive, &params_entry).await;
+		std::fs::remove_dir_all(&dir).ok();
+
+		assert!(annual.backfill);
+		assert_eq!(annual.fetched_at, fetched_at.naive_local());
+		assert_eq!(annual.annuals.len(), 3);
+		assert!(annual.quarters.is_empty());
+		assert_eq!(quarter.quarters.len(), 2);
+		assert!(quarter.annuals.is_empty());
+		assert!(params.is_err());
+	}
+
+	#[test]
+	fn script_values() {
+		let page = fixture!("c1010001_005930.html");
+		assert_eq!(find_script_value(page, "encparam"), Some("dU5aRkp2bmVjS0NUR0lTU0w3Q3c9PQ=="));
+		// `gid`, `uid` 가 아닌 `id`
+		assert_eq!(find_script_value(page, "id"), Some("TFhzeVhZNT"));
+		assert_eq!(find_script_value(page, "token"), None);
+	}
+}
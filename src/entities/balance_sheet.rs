@@ -0,0 +1,67 @@
+use async_trait::async_trait;
+use chrono::NaiveDate;
+
+use crate::types::{Amount, Error, FiTerm, StatementBasis, YearMonth};
+
+/// 재무상태표의 주요 항목 (단위: 억원)
+#[derive(Debug, Clone, PartialEq)]
+pub struct BalanceSheet {
+	/// 단축코드
+	pub stock_code: String,
+	/// 연간/분기 구분
+	pub term: FiTerm,
+	/// 연결/별도 구분
+	pub basis: StatementBasis,
+	/// 기준년월
+	pub year_month: YearMonth,
+	/// 자산총계
+	pub total_assets: Option<Amount>,
+	/// 부채총계
+	pub total_liabilities: Option<Amount>,
+	/// 자본총계
+	pub total_equity: Option<Amount>,
+	/// 지배기업 소유주지분
+	pub owners_equity: Option<Amount>,
+	/// 현금및현금성자산
+	pub cash: Option<Amount>,
+	/// 차입금 (단기차입금, 유동성장기부채, 장기차입금, 사채의 합)
+	pub borrowings: Option<Amount>,
+}
+
+impl BalanceSheet {
+	/// 순차입금. 차입금에서 현금및현금성자산을 뺀 값
+	pub fn net_borrowings(&self) -> Option<Amount> {
+		Some(self.borrowings? - self.cash?)
+	}
+
+	/// 수치 항목의 컬럼 이름과 값
+	pub fn figures(&self) -> [(&'static str, Option<Amount>); 6] {
+		[
+			("total_assets", self.total_assets),
+			("total_liabilities", self.total_liabilities),
+			("total_equity", self.total_equity),
+			("owners_equity", self.owners_equity),
+			("cash", self.cash),
+			("borrowings", self.borrowings),
+		]
+	}
+
+	/// 값이 달라진 항목의 (컬럼 이름, 이전 값, 새 값)
+	pub fn changed_figures(&self, new: &BalanceSheet) -> Vec<(&'static str, Option<Amount>, Option<Amount>)> {
+		self.figures().into_iter()
+			.zip(new.figures())
+			.filter(|((_, old), (_, new))| old != new)
+			.map(|((name, old), (_, new))| (name, old, new))
+			.collect()
+	}
+}
+
+#[async_trait]
+pub trait BalanceSheetsDao {
+	/// 년월 순
+	async fn list(&self, stock_code: &str, term: FiTerm) -> Result<Vec<BalanceSheet>, Error>;
+	/// `first_seen`: 해당 기간의 재무상태표를 처음 수집한 날짜
+	async fn insert(&self, balance_sheet: &BalanceSheet, first_seen: NaiveDate) -> Result<(), Error>;
+	/// 같은 기간의 수치 항목을 바꾼다. `first_seen` 은 그대로 둔다
+	async fn update(&self, balance_sheet: &BalanceSheet) -> Result<(), Error>;
+}
@@ -0,0 +1,148 @@
+use async_trait::async_trait;
+use chrono::NaiveDate;
+
+use crate::types::{Amount, Error, FiTerm, FiscalCalendar, StatementBasis, YearMonth};
+
+/// 현금흐름표의 주요 항목 (단위: 억원).
+///
+/// 분기보고서의 현금흐름은 회계연도 초부터 기준월까지의 누적 금액이라 그대로 저장하고 `cumulative` 로 표시한다.
+/// 3개월 금액은 `CashFlow::three_months` 로 바꿔서 쓴다.
+#[derive(Debug, Clone, PartialEq)]
+pub struct CashFlow {
+	/// 단축코드
+	pub stock_code: String,
+	/// 연간/분기 구분
+	pub term: FiTerm,
+	/// 연결/별도 구분
+	pub basis: StatementBasis,
+	/// 기준년월
+	pub year_month: YearMonth,
+	/// 영업활동현금흐름
+	pub operating: Option<Amount>,
+	/// 투자활동현금흐름
+	pub investing: Option<Amount>,
+	/// 재무활동현금흐름
+	pub financing: Option<Amount>,
+	/// 유형자산의 취득 (CAPEX). 지출을 양수로 저장한다
+	pub capex: Option<Amount>,
+	/// 잉여현금흐름 (FCF)
+	pub fcf: Option<Amount>,
+	/// 회계연도 초부터 기준월까지의 누적 금액인지
+	pub cumulative: bool,
+}
+
+impl CashFlow {
+	/// 영업활동현금흐름에서 CAPEX 를 뺀 잉여현금흐름
+	pub fn free_cash_flow(operating: Option<Amount>, capex: Option<Amount>) -> Option<Amount> {
+		Some(operating? - capex?)
+	}
+
+	/// 수치 항목의 컬럼 이름과 값
+	pub fn figures(&self) -> [(&'static str, Option<Amount>); 5] {
+		[
+			("operating", self.operating),
+			("investing", self.investing),
+			("financing", self.financing),
+			("capex", self.capex),
+			("fcf", self.fcf),
+		]
+	}
+
+	/// 값이 달라진 항목의 (컬럼 이름, 이전 값, 새 값)
+	pub fn changed_figures(&self, new: &CashFlow) -> Vec<(&'static str, Option<Amount>, Option<Amount>)> {
+		self.figures().into_iter()
+			.zip(new.figures())
+			.filter(|((_, old), (_, new))| old != new)
+			.map(|((name, old), (_, new))| (name, old, new))
+			.collect()
+	}
+
+	/// 누적 금액을 3개월 금액으로 바꾼다. 회계연도 첫 분기는 그대로이고,
+	/// 나머지 분기는 같은 구분의 직전 분기 누적 금액을 뺀다. 직전 분기가 없으면 금액을 비운다
+	pub fn three_months(cash_flows: &[CashFlow], calendar: FiscalCalendar) -> Vec<CashFlow> {
+		cash_flows.iter()
+			.map(|cf| {
+				if !cf.cumulative || calendar.quarter_index(cf.year_month) == 1 {
+					return CashFlow { cumulative: false, ..cf.clone() };
+				}
+				let prev_month = cf.year_month.sub_quarters(1);
+				let prev = cash_flows.iter()
+					.find(|prev| prev.cumulative && prev.term == cf.term && prev.basis == cf.basis && prev.year_month == prev_month);
+				let diff = |value: fn(&CashFlow) -> Option<Amount>| prev.and_then(|prev| Some(value(cf)? - value(prev)?));
+				CashFlow {
+					operating: diff(|cf| cf.operating),
+					investing: diff(|cf| cf.investing),
+					financing: diff(|cf| cf.financing),
+					capex: diff(|cf| cf.capex),
+					fcf: diff(|cf| cf.fcf),
+					cumulative: false,
+					..cf.clone()
+				}
+			})
+			.collect()
+	}
+}
+
+#[async_trait]
+pub trait CashFlowsDao {
+	/// 년월 순
+	async fn list(&self, stock_code: &str, term: FiTerm) -> Result<Vec<CashFlow>, Error>;
+	/// `first_seen`: 해당 기간의 현금흐름표를 처음 수집한 날짜
+	async fn insert(&self, cash_flow: &CashFlow, first_seen: NaiveDate) -> Result<(), Error>;
+	/// 같은 기간의 수치 항목을 바꾼다. `first_seen` 은 그대로 둔다
+	async fn update(&self, cash_flow: &CashFlow) -> Result<(), Error>;
+}
+
+#[cfg(test)]
+mod tests {
+	use rust_decimal::Decimal;
+
+	use super::*;
+
+	fn cumulative(year_month: &str, operating: i64, capex: i64) -> CashFlow {
+		CashFlow {
+			stock_code: "005930".to_string(),
+			term: FiTerm::Quarter,
+			basis: StatementBasis::Consolidated,
+			year_month: year_month.parse().unwrap(),
+			operating: Some(Decimal::from(operating)),
+			investing: None,
+			financing: None,
+			capex: Some(Decimal::from(capex)),
+			fcf: Some(Decimal::from(operating - capex)),
+			cumulative: true,
+		}
+	}
+
+	#[test]
+	fn three_months_from_cumulative() {
+		let list = [cumulative("2024.03", 100, 30), cumulative("2024.06", 250, 70), cumulative("2024.09", 420, 100)];
+		let quarters = CashFlow::three_months(&list, FiscalCalendar::DECEMBER);
+		let figures: Vec<_> = quarters.iter().map(|cf| (cf.operating, cf.capex, cf.fcf, cf.cumulative)).collect();
+		assert_eq!(figures, vec![
+			(Some(Decimal::from(100)), Some(Decimal::from(30)), Some(Decimal::from(70)), false),
+			(Some(Decimal::from(150)), Some(Decimal::from(40)), Some(Decimal::from(110)), false),
+			(Some(Decimal::from(170)), Some(Decimal::from(30)), Some(Decimal::from(140)), false),
+		]);
+		// 항목이 없으면 차이도 없다
+		assert_eq!(quarters[1].investing, None);
+	}
+
+	#[test]
+	fn three_months_needs_previous_quarter() {
+		let list = [cumulative("2024.03", 100, 30), cumulative("2024.09", 420, 100)];
+		let quarters = CashFlow::three_months(&list, FiscalCalendar::DECEMBER);
+		assert_eq!(quarters[1].operating, None);
+		assert_eq!(quarters[1].fcf, None);
+	}
+
+	#[test]
+	fn three_months_starts_at_fiscal_year() {
+		// 3월 결산법인의 1분기는 6월
+		let march = FiscalCalendar::new(3).unwrap();
+		let list = [cumulative("2024.03", 500, 200), cumulative("2024.06", 120, 40)];
+		let quarters = CashFlow::three_months(&list, march);
+		assert_eq!(quarters[1].operating, Some(Decimal::from(120)));
+		assert_eq!(quarters[1].capex, Some(Decimal::from(40)));
+	}
+}
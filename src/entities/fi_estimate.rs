@@ -0,0 +1,26 @@
+use async_trait::async_trait;
+use chrono::NaiveDate;
+
+use crate::{entities::FinancialInfo, types::{Error, FiTerm}};
+
+/// 컨센서스 추정실적(E)의 수집일자별 스냅샷
+#[derive(Debug, PartialEq, Clone)]
+pub struct FiEstimate {
+	/// 연간/분기 구분
+	pub term: FiTerm,
+	/// 수집일자
+	pub capture_date: NaiveDate,
+	/// 추정실적
+	pub info: FinancialInfo,
+}
+
+#[async_trait]
+pub trait FiEstimatesDao {
+	/// 수집된 모든 스냅샷 (수집일자, 년월 순)
+	async fn list(&self, stock_code: &str) -> Result<Vec<FiEstimate>, Error>;
+	/// 가장 최근 수집일자의 스냅샷
+	async fn latest(&self, stock_code: &str) -> Result<Vec<FiEstimate>, Error>;
+	/// `date` 까지 수집된 것 중 가장 최근 수집일자의 스냅샷
+	async fn latest_as_of(&self, stock_code: &str, date: NaiveDate) -> Result<Vec<FiEstimate>, Error>;
+	async fn upsert(&self, estimate: &FiEstimate) -> Result<(), Error>;
+}
@@ -0,0 +1,53 @@
+use async_trait::async_trait;
+use chrono::{NaiveDate, NaiveDateTime};
+
+use crate::{entities::FinancialInfo, types::{Amount, Error, FiTerm, StatementBasis, YearMonth}};
+
+/// 이미 저장된 실적, 재무상태표, 현금흐름표 항목이 바뀐 기록. 추가만 하고 고치거나 지우지 않는다
+///
+/// 백필한 값이 저장된 값과 다르면 저장된 값을 그대로 두고 `applied` 가 `false` 인 기록만 남긴다.
+#[derive(Debug, Clone)]
+pub struct FiRevision {
+	/// 단축코드
+	pub stock_code: String,
+	/// 연간/분기 구분
+	pub term: FiTerm,
+	/// 연결/별도 구분
+	pub basis: StatementBasis,
+	/// 년월
+	pub year_month: YearMonth,
+	/// 컬럼 이름 (예: `sales`). 재무상태표/현금흐름표의 컬럼 이름은 실적 컬럼과 겹치지 않는다
+	pub field: String,
+	pub old_value: Option<Amount>,
+	pub new_value: Option<Amount>,
+	/// 새 값이 저장되었는지. 반영하지 않은 백필 값이면 `false`
+	pub applied: bool,
+	/// 데이터 소스 이름
+	pub source: String,
+	/// 수정된 값을 수집한 시각
+	pub revised_at: NaiveDateTime,
+}
+
+impl FiRevision {
+	/// `date` 이후의 수정 내역을 최근 것부터 되돌려서 그날 알려져 있던 값으로 만든다
+	pub fn revert_after(infos: &mut [FinancialInfo], term: FiTerm, revisions: &[FiRevision], date: NaiveDate) {
+		let mut revisions: Vec<&FiRevision> = revisions.iter()
+			.filter(|rev| rev.applied && rev.term == term && rev.revised_at.date() > date)
+			.collect();
+		revisions.sort_by_key(|rev| rev.revised_at);
+
+		for rev in revisions.into_iter().rev() {
+			let info = infos.iter_mut().find(|fi| fi.year_month == rev.year_month && fi.basis == rev.basis);
+			if let Some(value) = info.and_then(|fi| fi.figure_mut(&rev.field)) {
+				*value = rev.old_value;
+			}
+		}
+	}
+}
+
+#[async_trait]
+pub trait FiRevisionsDao {
+	/// 수정 내역 (수정시각 순)
+	async fn list(&self, stock_code: &str, term: FiTerm) -> Result<Vec<FiRevision>, Error>;
+	async fn insert(&self, revision: &FiRevision) -> Result<(), Error>;
+}
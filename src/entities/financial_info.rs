@@ -1,6 +1,7 @@
 use async_trait::async_trait;
+use chrono::NaiveDate;
 
-use crate::types::{Error, YearMonth};
+use crate::types::{Amount, Error, StatementBasis, Unit, YearMonth};
 
 #[derive(Debug, Default, PartialEq, Clone)]
 pub struct FinancialInfo {
@@ -8,31 +9,144 @@ pub struct FinancialInfo {
 	pub stock_code: String,
 	/// 년월
 	pub year_month: YearMonth,
+	/// 연결/별도 구분
+	pub basis: StatementBasis,
 	/// 매출액
-	pub sales: Option<f32>,
+	pub sales: Option<Amount>,
 	/// 영업이익
-	pub profit: Option<f32>,
+	pub profit: Option<Amount>,
 	/// 당기순이익
-	pub net_income: Option<f32>,
+	pub net_income: Option<Amount>,
+	/// 영업이익률
+	pub operating_margin: Option<Amount>,
+	/// 순이익률
+	pub net_margin: Option<Amount>,
+	/// ROE(지배주주)
+	pub roe: Option<Amount>,
+	/// 부채비율
+	pub debt_ratio: Option<Amount>,
+	/// 당좌비율
+	pub quick_ratio: Option<Amount>,
+	/// 유보율
+	pub reserve_ratio: Option<Amount>,
+	/// EPS(원)
+	pub eps: Option<Amount>,
+	/// PER(배)
+	pub per: Option<Amount>,
+	/// BPS(원)
+	pub bps: Option<Amount>,
+	/// PBR(배)
+	pub pbr: Option<Amount>,
 	/// 주당배당금
-	pub dividend: Option<f32>,
+	pub dividend: Option<Amount>,
 	/// 배당수익률
-	pub dividend_yield: Option<f32>,
+	pub dividend_yield: Option<Amount>,
+	/// 발표된 실적이 아니라 연간 실적에서 계산한 분기 실적
+	pub derived: bool,
+}
+
+impl FinancialInfo {
+	/// 매출액, 영업이익, 당기순이익의 저장 단위
+	pub const AMOUNT_UNIT: Unit = Unit::HundredMillionWon;
+	/// 저장하는 수치의 소수점 자리수 (DB 컬럼과 같다)
+	pub const FIGURE_SCALE: u32 = 2;
+
+	/// 항목별 저장 단위. 금액은 억원, 주당 금액은 원, 비율은 % 또는 배
+	pub fn unit_of(name: &str) -> Option<Unit> {
+		match name {
+			"sales" | "profit" | "net_income" => Some(Self::AMOUNT_UNIT),
+			"eps" | "bps" | "dividend" => Some(Unit::Won),
+			"per" | "pbr" => Some(Unit::Multiple),
+			"operating_margin" | "net_margin" | "roe" | "debt_ratio" | "quick_ratio" | "reserve_ratio" | "dividend_yield" => Some(Unit::Percent),
+			_ => None,
+		}
+	}
+
+	/// 수치 항목의 컬럼 이름과 값
+	pub fn figures(&self) -> [(&'static str, Option<Amount>); 15] {
+		[
+			("sales", self.sales),
+			("profit", self.profit),
+			("net_income", self.net_income),
+			("operating_margin", self.operating_margin),
+			("net_margin", self.net_margin),
+			("roe", self.roe),
+			("debt_ratio", self.debt_ratio),
+			("quick_ratio", self.quick_ratio),
+			("reserve_ratio", self.reserve_ratio),
+			("eps", self.eps),
+			("per", self.per),
+			("bps", self.bps),
+			("pbr", self.pbr),
+			("dividend", self.dividend),
+			("dividend_yield", self.dividend_yield),
+		]
+	}
+
+	pub fn figure_mut(&mut self, name: &str) -> Option<&mut Option<Amount>> {
+		match name {
+			"sales" => Some(&mut self.sales),
+			"profit" => Some(&mut self.profit),
+			"net_income" => Some(&mut self.net_income),
+			"operating_margin" => Some(&mut self.operating_margin),
+			"net_margin" => Some(&mut self.net_margin),
+			"roe" => Some(&mut self.roe),
+			"debt_ratio" => Some(&mut self.debt_ratio),
+			"quick_ratio" => Some(&mut self.quick_ratio),
+			"reserve_ratio" => Some(&mut self.reserve_ratio),
+			"eps" => Some(&mut self.eps),
+			"per" => Some(&mut self.per),
+			"bps" => Some(&mut self.bps),
+			"pbr" => Some(&mut self.pbr),
+			"dividend" => Some(&mut self.dividend),
+			"dividend_yield" => Some(&mut self.dividend_yield),
+			_ => None,
+		}
+	}
+
+	/// 값이 달라진 항목의 (컬럼 이름, 이전 값, 새 값)
+	pub fn changed_figures(&self, new: &FinancialInfo) -> Vec<(&'static str, Option<Amount>, Option<Amount>)> {
+		self.figures().into_iter()
+			.zip(new.figures())
+			.filter(|((_, old), (_, new))| old != new)
+			.map(|((name, old), (_, new))| (name, old, new))
+			.collect()
+	}
 }
 
 pub struct FiAnnualData {
 	/// 기준년월
 	pub month: u8,
 	/// 매출액
-	pub sales: Option<f32>,
+	pub sales: Option<Amount>,
 	/// 영업이익
-	pub profit: Option<f32>,
+	pub profit: Option<Amount>,
 	/// 당기순이익
-	pub net_income: Option<f32>,
+	pub net_income: Option<Amount>,
+	/// 영업이익률
+	pub operating_margin: Option<Amount>,
+	/// 순이익률
+	pub net_margin: Option<Amount>,
+	/// ROE(지배주주)
+	pub roe: Option<Amount>,
+	/// 부채비율
+	pub debt_ratio: Option<Amount>,
+	/// 당좌비율
+	pub quick_ratio: Option<Amount>,
+	/// 유보율
+	pub reserve_ratio: Option<Amount>,
+	/// EPS(원)
+	pub eps: Option<Amount>,
+	/// PER(배)
+	pub per: Option<Amount>,
+	/// BPS(원)
+	pub bps: Option<Amount>,
+	/// PBR(배)
+	pub pbr: Option<Amount>,
 	/// 주당배당금
-	pub dividend: Option<f32>,
+	pub dividend: Option<Amount>,
 	/// 배당수익률
-	pub dividend_yield: Option<f32>,
+	pub dividend_yield: Option<Amount>,
 }
 impl From<&FinancialInfo> for FiAnnualData {
 	fn from(info: &FinancialInfo) -> Self {
@@ -41,6 +155,16 @@ impl From<&FinancialInfo> for FiAnnualData {
 			sales: info.sales,
 			profit: info.profit,
 			net_income: info.net_income,
+			operating_margin: info.operating_margin,
+			net_margin: info.net_margin,
+			roe: info.roe,
+			debt_ratio: info.debt_ratio,
+			quick_ratio: info.quick_ratio,
+			reserve_ratio: info.reserve_ratio,
+			eps: info.eps,
+			per: info.per,
+			bps: info.bps,
+			pbr: info.pbr,
 			dividend: info.dividend,
 			dividend_yield: info.dividend_yield,
 		}
@@ -49,15 +173,37 @@ impl From<&FinancialInfo> for FiAnnualData {
 
 pub struct FiQuarterData {
 	/// 매출액
-	pub sales: Option<f32>,
+	pub sales: Option<Amount>,
 	/// 영업이익
-	pub profit: Option<f32>,
+	pub profit: Option<Amount>,
 	/// 당기순이익
-	pub net_income: Option<f32>,
+	pub net_income: Option<Amount>,
+	/// 영업이익률
+	pub operating_margin: Option<Amount>,
+	/// 순이익률
+	pub net_margin: Option<Amount>,
+	/// ROE(지배주주)
+	pub roe: Option<Amount>,
+	/// 부채비율
+	pub debt_ratio: Option<Amount>,
+	/// 당좌비율
+	pub quick_ratio: Option<Amount>,
+	/// 유보율
+	pub reserve_ratio: Option<Amount>,
+	/// EPS(원)
+	pub eps: Option<Amount>,
+	/// PER(배)
+	pub per: Option<Amount>,
+	/// BPS(원)
+	pub bps: Option<Amount>,
+	/// PBR(배)
+	pub pbr: Option<Amount>,
 	/// 주당배당금
-	pub dividend: Option<f32>,
+	pub dividend: Option<Amount>,
 	/// 배당수익률
-	pub dividend_yield: Option<f32>,
+	pub dividend_yield: Option<Amount>,
+	/// 연간 실적에서 계산한 분기 실적
+	pub derived: bool,
 }
 impl From<&FinancialInfo> for FiQuarterData {
 	fn from(info: &FinancialInfo) -> Self {
@@ -65,24 +211,47 @@ impl From<&FinancialInfo> for FiQuarterData {
 			sales: info.sales,
 			profit: info.profit,
 			net_income: info.net_income,
+			operating_margin: info.operating_margin,
+			net_margin: info.net_margin,
+			roe: info.roe,
+			debt_ratio: info.debt_ratio,
+			quick_ratio: info.quick_ratio,
+			reserve_ratio: info.reserve_ratio,
+			eps: info.eps,
+			per: info.per,
+			bps: info.bps,
+			pbr: info.pbr,
 			dividend: info.dividend,
 			dividend_yield: info.dividend_yield,
+			derived: info.derived,
 		}
 	}
 }
 
 #[async_trait]
 pub trait FiAnnualsDao {
-	async fn find(&self, stock_code: &str, year: u16) -> Result<Option<FinancialInfo>, Error>;
+	async fn find(&self, stock_code: &str, basis: StatementBasis, year: u16) -> Result<Option<FinancialInfo>, Error>;
+	/// 년월, 연결/별도 구분 순
 	async fn list(&self, stock_code: &str) -> Result<Vec<FinancialInfo>, Error>;
-	async fn insert(&self, annual: &FinancialInfo) -> Result<(), Error>;
+	/// `first_seen`: 해당 기간의 실적을 처음 수집한 날짜
+	async fn insert(&self, annual: &FinancialInfo, first_seen: NaiveDate) -> Result<(), Error>;
 	async fn update(&self, annual: &mut FinancialInfo, data: FiAnnualData) -> Result<(), Error>;
+	/// `date` 에 알려져 있던 값. 이후의 수정 내역을 되돌린다
+	async fn list_known_on(&self, stock_code: &str, date: NaiveDate) -> Result<Vec<FinancialInfo>, Error>;
+	/// `date` 까지 수집된 기간만, 그날 알려져 있던 값으로. 년월, 연결/별도 구분 순
+	async fn list_as_of(&self, stock_code: &str, date: NaiveDate) -> Result<Vec<FinancialInfo>, Error>;
 }
 
 #[async_trait]
 pub trait FiQuartersDao {
-	async fn find(&self, stock_code: &str, year: u16, month: u8) -> Result<Option<FinancialInfo>, Error>;
+	async fn find(&self, stock_code: &str, basis: StatementBasis, year: u16, month: u8) -> Result<Option<FinancialInfo>, Error>;
+	/// 년월, 연결/별도 구분 순
 	async fn list(&self, stock_code: &str) -> Result<Vec<FinancialInfo>, Error>;
-	async fn insert(&self, quarter: &FinancialInfo) -> Result<(), Error>;
+	/// `first_seen`: 해당 기간의 실적을 처음 수집한 날짜
+	async fn insert(&self, quarter: &FinancialInfo, first_seen: NaiveDate) -> Result<(), Error>;
 	async fn update(&self, quarter: &mut FinancialInfo, data: FiQuarterData) -> Result<(), Error>;
+	/// `date` 에 알려져 있던 값. 이후의 수정 내역을 되돌린다
+	async fn list_known_on(&self, stock_code: &str, date: NaiveDate) -> Result<Vec<FinancialInfo>, Error>;
+	/// `date` 까지 수집된 기간만, 그날 알려져 있던 값으로. 년월, 연결/별도 구분 순
+	async fn list_as_of(&self, stock_code: &str, date: NaiveDate) -> Result<Vec<FinancialInfo>, Error>;
 }
@@ -0,0 +1,45 @@
+use async_trait::async_trait;
+
+use crate::{entities::FinancialInfo, types::{Error, FiscalCalendar, YearMonth}};
+
+/// 종목별 결산월
+#[derive(Debug, Clone, PartialEq)]
+pub struct FiscalMonth {
+	/// 단축코드
+	pub stock_code: String,
+	/// 결산월
+	pub month: u8,
+	/// 이 결산월로 끝난 첫 회계연도. 결산월을 바꾼 뒤라면 변경 후 첫 (짧은) 회계연도
+	pub since: YearMonth,
+}
+
+impl FiscalMonth {
+	/// 가장 최근 연간 실적의 기준월을 결산월로 본다
+	pub fn from_annuals(stock_code: &str, annuals: &[FinancialInfo]) -> Option<Self> {
+		let mut annuals: Vec<YearMonth> = annuals.iter().map(|fi| fi.year_month).collect();
+		annuals.sort();
+		let latest = *annuals.last()?;
+		let since = annuals.iter().rev()
+			.take_while(|ym| ym.month == latest.month)
+			.last()
+			.copied()
+			.unwrap_or(latest);
+		Some(Self {
+			stock_code: stock_code.to_string(),
+			month: latest.month,
+			since,
+		})
+	}
+
+	pub fn calendar(&self) -> FiscalCalendar {
+		FiscalCalendar { fiscal_month: self.month }
+	}
+}
+
+#[async_trait]
+pub trait FiscalMonthsDao {
+	/// 결산월이 저장된 모든 종목
+	async fn list(&self) -> Result<Vec<FiscalMonth>, Error>;
+	async fn find(&self, stock_code: &str) -> Result<Option<FiscalMonth>, Error>;
+	async fn upsert(&self, fiscal_month: &FiscalMonth) -> Result<(), Error>;
+}
@@ -1,8 +1,10 @@
 use async_trait::async_trait;
 use chrono::NaiveDate;
+use rust_decimal::Decimal;
 
 use crate::types::{Error, Market};
 
+#[derive(Debug, Clone)]
 pub struct ProductItem {
 	/// 단축코드
 	pub code: String,
@@ -31,7 +33,7 @@ pub struct ProductItem {
 	/// 기초지수산출기관
 	pub etf_idx_inst: Option<String>,
 	/// 추적배수
-	pub etf_idx_multiplier: Option<String>,
+	pub etf_idx_multiplier: Option<i32>,
 	/// 복제방법
 	pub etf_replica_method: Option<String>,
 	/// 기초시장분류
@@ -40,15 +42,66 @@ pub struct ProductItem {
 	pub etf_idx_asset: Option<String>,
 	/// 운용사
 	pub etf_op_company: Option<String>,
-	/// 총보수
-	pub etf_fee_rate: Option<f32>,
+	/// 총보수 (%)
+	pub etf_fee_rate: Option<Decimal>,
 	/// 과세유형
 	pub etf_tax_type: Option<String>,
 }
 
 #[async_trait]
 pub trait ProductItemsDao {
-	async fn list(&self) -> Result<Vec<ProductItem>, Error>;
+	async fn list(&self, markets: &[Market]) -> Result<Vec<ProductItem>, Error>;
+	async fn upsert(&self, item: &ProductItem) -> Result<(), Error>;
+}
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum ListingChangeKind {
+	/// 신규상장
+	Listed,
+	/// 상장폐지
+	Delisted,
+}
+
+impl ListingChangeKind {
+	pub fn as_str(&self) -> &'static str {
+		match self {
+			ListingChangeKind::Listed => "LISTED",
+			ListingChangeKind::Delisted => "DELISTED",
+		}
+	}
+}
+
+impl TryFrom<&str> for ListingChangeKind {
+	type Error = Error;
+
+	fn try_from(value: &str) -> Result<Self, Self::Error> {
+		match value {
+			"LISTED" => Ok(ListingChangeKind::Listed),
+			"DELISTED" => Ok(ListingChangeKind::Delisted),
+			_ => Err(format!("Unknown ListingChangeKind Representation `{value}`").into()),
+		}
+	}
+}
+
+/// 종목마스터 갱신시 발견된 신규상장/상장폐지 종목
+#[derive(Debug, Clone)]
+pub struct ListingChange {
+	/// 단축코드
+	pub code: String,
+	/// 변동 확인일자
+	pub change_date: NaiveDate,
+	/// 변동구분
+	pub kind: ListingChangeKind,
+	/// 종목명
+	pub name: String,
+	/// 시장구분
+	pub market: Market,
+}
+
+#[async_trait]
+pub trait ListingChangesDao {
+	async fn list(&self) -> Result<Vec<ListingChange>, Error>;
+	async fn insert(&self, change: &ListingChange) -> Result<(), Error>;
 }
 
 pub struct Stock {
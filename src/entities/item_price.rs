@@ -20,6 +20,10 @@ pub struct StockPrice {
 	pub closing: Option<u32>,
 	/// 전일대비
 	pub diff: Option<i32>,
+	/// 거래량
+	pub volume: Option<u64>,
+	/// 거래대금
+	pub trading_value: Option<u64>,
 }
 
 pub struct StockPriceRange {
@@ -32,5 +36,9 @@ pub struct StockPriceRange {
 #[async_trait]
 pub trait StockPricesDao {
 	async fn latest(&self, code: &str) -> Result<Option<StockPrice>, Error>;
+	/// `date` 또는 그 이전의 마지막 거래일 시세
+	async fn latest_as_of(&self, code: &str, date: NaiveDate) -> Result<Option<StockPrice>, Error>;
 	async fn range(&self, code: &str, range: Range<NaiveDate>) -> Result<Option<StockPriceRange>, Error>;
+	/// (code, ord_date) 기준으로 일괄 저장
+	async fn upsert(&self, prices: &[StockPrice]) -> Result<(), Error>;
 }
@@ -1,9 +1,21 @@
 mod item_info;
 mod item_price;
 mod financial_info;
+mod fi_estimate;
+mod fi_revision;
+mod fiscal_month;
+mod balance_sheet;
+mod cash_flow;
+mod scrape_run;
 mod repo_spec;
 
 pub use item_info::*;
 pub use item_price::*;
 pub use financial_info::*;
+pub use fi_estimate::*;
+pub use fi_revision::*;
+pub use fiscal_month::*;
+pub use balance_sheet::*;
+pub use cash_flow::*;
+pub use scrape_run::*;
 pub use repo_spec::*;
@@ -1,12 +1,21 @@
 use async_trait::async_trait;
 
-use crate::{entities::{FiAnnualsDao, FiQuartersDao, StockPricesDao, StocksDao}, types::Error};
+use crate::{entities::{BalanceSheetsDao, CashFlowsDao, FiAnnualsDao, FiEstimatesDao, FiQuartersDao, FiRevisionsDao, FiscalMonthsDao, ListingChangesDao, ProductItemsDao, ScrapeRunItemsDao, ScrapeRunsDao, StockPricesDao, StocksDao}, types::Error};
 
 pub trait EntityDao: Send + Sync {
 	fn stocks(&self) -> &(dyn StocksDao + Sync);
+	fn product_items(&self) -> &(dyn ProductItemsDao + Sync);
+	fn listing_changes(&self) -> &(dyn ListingChangesDao + Sync);
 	fn stock_prices(&self) -> &(dyn StockPricesDao + Sync);
 	fn fi_annuals(&self) -> &(dyn FiAnnualsDao + Sync);
 	fn fi_quarters(&self) -> &(dyn FiQuartersDao + Sync);
+	fn fi_estimates(&self) -> &(dyn FiEstimatesDao + Sync);
+	fn scrape_runs(&self) -> &(dyn ScrapeRunsDao + Sync);
+	fn scrape_run_items(&self) -> &(dyn ScrapeRunItemsDao + Sync);
+	fn fi_revisions(&self) -> &(dyn FiRevisionsDao + Sync);
+	fn fiscal_months(&self) -> &(dyn FiscalMonthsDao + Sync);
+	fn balance_sheets(&self) -> &(dyn BalanceSheetsDao + Sync);
+	fn cash_flows(&self) -> &(dyn CashFlowsDao + Sync);
 }
 
 #[async_trait]
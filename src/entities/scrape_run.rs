@@ -0,0 +1,76 @@
+use async_trait::async_trait;
+use chrono::NaiveDateTime;
+
+use crate::types::Error;
+
+/// `stock-fn-scrap` 의 실행 기록
+#[derive(Debug, Clone)]
+pub struct ScrapeRun {
+	pub id: u64,
+	/// 데이터 소스 이름
+	pub source: String,
+	pub started_at: NaiveDateTime,
+	/// 오류 없이 모든 종목을 처리했을 때만 기록된다
+	pub finished_at: Option<NaiveDateTime>,
+}
+
+#[async_trait]
+pub trait ScrapeRunsDao {
+	/// 해당 소스의 가장 최근 미완료 실행
+	async fn last_unfinished(&self, source: &str) -> Result<Option<ScrapeRun>, Error>;
+	async fn insert(&self, source: &str, started_at: NaiveDateTime) -> Result<ScrapeRun, Error>;
+	async fn finish(&self, id: u64, finished_at: NaiveDateTime) -> Result<(), Error>;
+}
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum ScrapeItemStatus {
+	Fetched,
+	Skipped,
+	Errored,
+}
+
+impl ScrapeItemStatus {
+	pub fn as_str(&self) -> &'static str {
+		match self {
+			ScrapeItemStatus::Fetched => "FETCHED",
+			ScrapeItemStatus::Skipped => "SKIPPED",
+			ScrapeItemStatus::Errored => "ERRORED",
+		}
+	}
+
+	/// 이어서 실행할 때 다시 처리하지 않아도 되는 상태인지
+	pub fn is_done(&self) -> bool {
+		!matches!(self, ScrapeItemStatus::Errored)
+	}
+}
+
+impl TryFrom<&str> for ScrapeItemStatus {
+	type Error = Error;
+
+	fn try_from(value: &str) -> Result<Self, Self::Error> {
+		match value {
+			"FETCHED" => Ok(ScrapeItemStatus::Fetched),
+			"SKIPPED" => Ok(ScrapeItemStatus::Skipped),
+			"ERRORED" => Ok(ScrapeItemStatus::Errored),
+			_ => Err(format!("Unknown ScrapeItemStatus Representation `{value}`").into()),
+		}
+	}
+}
+
+/// 실행 중 종목별 처리 결과
+#[derive(Debug, Clone)]
+pub struct ScrapeRunItem {
+	pub run_id: u64,
+	pub stock_code: String,
+	pub status: ScrapeItemStatus,
+	/// 오류 메시지
+	pub message: Option<String>,
+	pub updated_at: NaiveDateTime,
+}
+
+#[async_trait]
+pub trait ScrapeRunItemsDao {
+	async fn list(&self, run_id: u64) -> Result<Vec<ScrapeRunItem>, Error>;
+	/// 같은 종목을 다시 처리하면 마지막 결과로 덮어쓴다
+	async fn upsert(&self, item: &ScrapeRunItem) -> Result<(), Error>;
+}
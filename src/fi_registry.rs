@@ -1,14 +1,37 @@
-use crate::{entities::{EntityDao, FinancialInfo}, repository::Repo, types::{Error, YearMonth}};
+use chrono::{Local, NaiveDate, NaiveDateTime};
+
+use crate::{analytics, entities::{BalanceSheet, CashFlow, EntityDao, FiEstimate, FiRevision, FinancialInfo, FiscalMonth}, repository::Repo, types::{Amount, Error, FiTerm, FiscalCalendar, StatementBasis, Unit, YearMonth}};
 
 pub struct Financials {
+	/// 데이터 소스 이름
+	pub source: &'static str,
+	/// 수집시각
+	pub fetched_at: NaiveDateTime,
+	/// 과거 실적을 채우기 위한 수집. 이미 저장된 값은 바꾸지 않고 비어 있는 항목만 채운다.
+	/// 새 기간의 `first_seen` 은 수집일 대신 정기보고서 제출기한으로 한다
+	pub backfill: bool,
 	pub annuals: FinancialInfoRegistry,
 	pub quarters: FinancialInfoRegistry,
+	/// 컨센서스 추정실적(E)
+	pub annual_estimates: FinancialInfoRegistry,
+	pub quarter_estimates: FinancialInfoRegistry,
+	/// 재무상태표 주요 항목
+	pub balance_sheets: Vec<BalanceSheet>,
+	/// 현금흐름표 주요 항목
+	pub cash_flows: Vec<CashFlow>,
 }
 impl Financials {
-	pub fn new(stock_code: &str) -> Self {
+	pub fn new(source: &'static str, stock_code: &str) -> Self {
 		Self {
+			source,
+			fetched_at: Local::now().naive_local(),
+			backfill: false,
 			annuals: FinancialInfoRegistry::new(stock_code),
 			quarters: FinancialInfoRegistry::new(stock_code),
+			annual_estimates: FinancialInfoRegistry::new(stock_code),
+			quarter_estimates: FinancialInfoRegistry::new(stock_code),
+			balance_sheets: Vec::new(),
+			cash_flows: Vec::new(),
 		}
 	}
 
@@ -17,25 +40,43 @@ impl Financials {
 		if !self.annuals.is_empty() {
 			log::debug!("save_annuals");
 			self.save_annuals(repo).await?;
+			self.save_fiscal_month(repo).await?;
 		}
 		if !self.quarters.is_empty() {
 			log::debug!("save_quarters");
 			self.save_quarters(repo).await?;
 		}
+		if !self.annuals.is_empty() || !self.quarters.is_empty() {
+			log::debug!("save_derived_quarters");
+			self.save_derived_quarters(repo).await?;
+		}
+		if !self.annual_estimates.is_empty() || !self.quarter_estimates.is_empty() {
+			log::debug!("save_estimates");
+			self.save_estimates(repo).await?;
+		}
+		if !self.balance_sheets.is_empty() || !self.cash_flows.is_empty() {
+			log::debug!("save_statements");
+			self.save_statements(repo).await?;
+		}
 		Ok(())
 	}
 
 	async fn save_annuals(&self, repo: &Repo) -> Result<(), Error> {
 		let mut list = repo.fi_annuals().list(&self.annuals.stock_code).await?;
+		let mut rejected = self.rejected_revisions(repo, FiTerm::Annual).await?;
 		for annual in self.annuals.iter() {
-			match list.iter_mut().find(|fi| fi.year_month.year == annual.year_month.year) {
+			// 결산월을 바꾼 해에는 같은 년도에 끝나는 회계연도가 둘이므로 년월로 찾는다
+			match list.iter_mut().find(|fi| fi.year_month == annual.year_month && fi.basis == annual.basis) {
 				Some(old) => {
-					if old != annual {
-						repo.fi_annuals().update(old, annual.into()).await?;
+					let new = self.merged(old, annual);
+					self.save_rejected(repo, FiTerm::Annual, &mut rejected, old, annual).await?;
+					if *old != new {
+						self.save_revisions(repo, FiTerm::Annual, old, &new).await?;
+						repo.fi_annuals().update(old, (&new).into()).await?;
 					}
 				}
 				None => {
-					repo.fi_annuals().insert(annual).await?;
+					repo.fi_annuals().insert(annual, self.first_seen(FiTerm::Annual, annual.year_month)).await?;
 				}
 			}
 		}
@@ -44,22 +85,221 @@ impl Financials {
 
 	async fn save_quarters(&self, repo: &Repo) -> Result<(), Error> {
 		let mut list = repo.fi_quarters().list(&self.quarters.stock_code).await?;
+		let mut rejected = self.rejected_revisions(repo, FiTerm::Quarter).await?;
 		for quarter in self.quarters.iter() {
-			match list.iter_mut().find(|fi| fi.year_month == quarter.year_month) {
+			match list.iter_mut().find(|fi| fi.year_month == quarter.year_month && fi.basis == quarter.basis) {
+				Some(old) => {
+					let new = self.merged(old, quarter);
+					self.save_rejected(repo, FiTerm::Quarter, &mut rejected, old, quarter).await?;
+					if *old != new {
+						self.save_revisions(repo, FiTerm::Quarter, old, &new).await?;
+						repo.fi_quarters().update(old, (&new).into()).await?;
+					}
+				}
+				None => {
+					repo.fi_quarters().insert(quarter, self.first_seen(FiTerm::Quarter, quarter.year_month)).await?;
+				}
+			}
+		}
+		Ok(())
+	}
+
+	/// 저장할 값. 백필이면 저장된 값은 그대로 두고 비어 있는 항목만 채운다.
+	/// 연간 실적에서 계산한 분기는 발표된 실적으로 바꾼다
+	fn merged(&self, old: &FinancialInfo, new: &FinancialInfo) -> FinancialInfo {
+		if !self.backfill || old.derived {
+			return new.clone();
+		}
+		let mut merged = old.clone();
+		for (name, value) in new.figures() {
+			if let Some(figure) = merged.figure_mut(name) && figure.is_none() {
+				*figure = value;
+			}
+		}
+		merged
+	}
+
+	/// 이미 남긴 반영하지 않은 백필 값. 백필이 아니면 필요 없다
+	async fn rejected_revisions(&self, repo: &Repo, term: FiTerm) -> Result<Vec<FiRevision>, Error> {
+		if !self.backfill {
+			return Ok(Vec::new());
+		}
+		let revisions = repo.fi_revisions().list(&self.annuals.stock_code, term).await?;
+		Ok(revisions.into_iter().filter(|rev| !rev.applied).collect())
+	}
+
+	/// 백필이 저장된 값과 다른 값을 가져왔으면 저장된 값을 두고 반영하지 않은 수정 내역으로 남긴다.
+	/// 같은 기간, 항목에 같은 백필 값을 이미 남겼으면 다시 남기지 않는다
+	async fn save_rejected(&self, repo: &Repo, term: FiTerm, rejected: &mut Vec<FiRevision>, old: &FinancialInfo, new: &FinancialInfo) -> Result<(), Error> {
+		if !self.backfill || old.derived {
+			return Ok(());
+		}
+		for (field, old_value, new_value) in old.changed_figures(new) {
+			if old_value.is_none() || new_value.is_none() {
+				continue;
+			}
+			let recorded = rejected.iter()
+				.any(|rev| rev.basis == old.basis && rev.year_month == old.year_month && rev.field == field && rev.new_value == new_value);
+			if recorded {
+				continue;
+			}
+			log::info!("Stock `{}` {} {} {} {}: kept {:?} over backfilled {:?}", old.stock_code, term.as_str(), old.basis.label(), old.year_month, field, old_value, new_value);
+			let revision = FiRevision {
+				applied: false,
+				..self.revision(term, old.basis, old.year_month, field, old_value, new_value)
+			};
+			repo.fi_revisions().insert(&revision).await?;
+			rejected.push(revision);
+		}
+		Ok(())
+	}
+
+	/// 새로 저장하는 기간의 `first_seen`.
+	/// 백필한 과거 실적은 제출기한에는 알려져 있었으므로 수집일 대신 제출기한으로 한다
+	fn first_seen(&self, term: FiTerm, year_month: YearMonth) -> NaiveDate {
+		let today = self.fetched_at.date();
+		if !self.backfill {
+			return today;
+		}
+		term.filing_deadline(year_month).min(today)
+	}
+
+	/// 저장된 연간 실적으로 결산월을 갱신한다
+	async fn save_fiscal_month(&self, repo: &Repo) -> Result<(), Error> {
+		let stock_code = &self.annuals.stock_code;
+		let annuals = repo.fi_annuals().list(stock_code).await?;
+		let Some(fiscal_month) = FiscalMonth::from_annuals(stock_code, &annuals) else {
+			return Ok(());
+		};
+		let old = repo.fiscal_months().find(stock_code).await?;
+		if old.as_ref() != Some(&fiscal_month) {
+			if let Some(old) = old.as_ref().filter(|old| old.month != fiscal_month.month) {
+				log::info!("Stock `{}` changed its fiscal month: {} -> {} (since {})", stock_code, old.month, fiscal_month.month, fiscal_month.since);
+			}
+			repo.fiscal_months().upsert(&fiscal_month).await?;
+		}
+		Ok(())
+	}
+
+	/// 저장된 연간/분기 실적으로 빠진 분기를 계산해서 저장한다. 발표된 분기는 덮어쓰지 않는다
+	async fn save_derived_quarters(&self, repo: &Repo) -> Result<(), Error> {
+		let stock_code = &self.quarters.stock_code;
+		let annuals = repo.fi_annuals().list(stock_code).await?;
+		let fiscal_month = repo.fiscal_months().find(stock_code).await?;
+		let calendar = fiscal_month.as_ref().map_or(FiscalCalendar::DECEMBER, |fm| fm.calendar());
+		let mut list = repo.fi_quarters().list(stock_code).await?;
+		for derived in analytics::derive_missing_quarters(&annuals, &list, fiscal_month.as_ref()) {
+			match list.iter_mut().find(|fi| fi.year_month == derived.year_month && fi.basis == derived.basis) {
 				Some(old) => {
-					if old != quarter {
-						repo.fi_quarters().update(old, quarter.into()).await?;
+					if old.derived && *old != derived {
+						self.save_revisions(repo, FiTerm::Quarter, old, &derived).await?;
+						repo.fi_quarters().update(old, (&derived).into()).await?;
 					}
 				}
 				None => {
-					repo.fi_quarters().insert(quarter).await?;
+					log::debug!("Derived quarter {} of stock `{}`", derived.year_month, stock_code);
+					// 연간 실적이 있어야 계산되므로 회계연도 사업보고서가 나온 뒤에 알 수 있다
+					let fiscal_year_end = calendar.fiscal_year_end(calendar.fiscal_year(derived.year_month));
+					repo.fi_quarters().insert(&derived, self.first_seen(FiTerm::Annual, fiscal_year_end)).await?;
 				}
 			}
 		}
 		Ok(())
 	}
+
+	/// 바뀐 항목마다 이전 값과 새 값을 남긴다
+	async fn save_revisions(&self, repo: &Repo, term: FiTerm, old: &FinancialInfo, new: &FinancialInfo) -> Result<(), Error> {
+		for (field, old_value, new_value) in old.changed_figures(new) {
+			log::info!("Stock `{}` {} {} {} {}: {:?} -> {:?}", old.stock_code, term.as_str(), old.basis.label(), old.year_month, field, old_value, new_value);
+			repo.fi_revisions().insert(&self.revision(term, old.basis, old.year_month, field, old_value, new_value)).await?;
+		}
+		Ok(())
+	}
+
+	fn revision(&self, term: FiTerm, basis: StatementBasis, year_month: YearMonth, field: &str, old_value: Option<Amount>, new_value: Option<Amount>) -> FiRevision {
+		FiRevision {
+			stock_code: self.annuals.stock_code.clone(),
+			term,
+			basis,
+			year_month,
+			field: field.to_string(),
+			old_value,
+			new_value,
+			applied: true,
+			source: self.source.to_string(),
+			revised_at: self.fetched_at,
+		}
+	}
+
+	async fn save_estimates(&self, repo: &Repo) -> Result<(), Error> {
+		let estimates = self.annual_estimates.iter().map(|fi| (FiTerm::Annual, fi))
+			.chain(self.quarter_estimates.iter().map(|fi| (FiTerm::Quarter, fi)));
+		for (term, info) in estimates {
+			let estimate = FiEstimate {
+				term,
+				capture_date: self.fetched_at.date(),
+				info: info.clone(),
+			};
+			repo.fi_estimates().upsert(&estimate).await?;
+		}
+		Ok(())
+	}
+
+	/// 재무상태표/현금흐름표. 이미 저장된 기간이 바뀌었으면 실적처럼 수정 내역을 남긴다
+	async fn save_statements(&self, repo: &Repo) -> Result<(), Error> {
+		let stock_code = &self.annuals.stock_code;
+		for term in [FiTerm::Annual, FiTerm::Quarter] {
+			let list = repo.balance_sheets().list(stock_code, term).await?;
+			for balance_sheet in self.balance_sheets.iter().filter(|bs| bs.term == term) {
+				match list.iter().find(|old| old.year_month == balance_sheet.year_month && old.basis == balance_sheet.basis) {
+					Some(old) => {
+						if old != balance_sheet {
+							self.save_statement_revisions(repo, term, old.basis, old.year_month, old.changed_figures(balance_sheet)).await?;
+							repo.balance_sheets().update(balance_sheet).await?;
+						}
+					}
+					None => {
+						repo.balance_sheets().insert(balance_sheet, self.first_seen(term, balance_sheet.year_month)).await?;
+					}
+				}
+			}
+
+			let list = repo.cash_flows().list(stock_code, term).await?;
+			for cash_flow in self.cash_flows.iter().filter(|cf| cf.term == term) {
+				match list.iter().find(|old| old.year_month == cash_flow.year_month && old.basis == cash_flow.basis) {
+					Some(old) => {
+						if old != cash_flow {
+							self.save_statement_revisions(repo, term, old.basis, old.year_month, old.changed_figures(cash_flow)).await?;
+							repo.cash_flows().update(cash_flow).await?;
+						}
+					}
+					None => {
+						repo.cash_flows().insert(cash_flow, self.first_seen(term, cash_flow.year_month)).await?;
+					}
+				}
+			}
+		}
+		Ok(())
+	}
+
+	async fn save_statement_revisions(&self, repo: &Repo, term: FiTerm, basis: StatementBasis, year_month: YearMonth, changes: Vec<(&'static str, Option<Amount>, Option<Amount>)>) -> Result<(), Error> {
+		for (field, old_value, new_value) in changes {
+			log::info!("Stock `{}` {} {} {} {}: {:?} -> {:?}", self.annuals.stock_code, term.as_str(), basis.label(), year_month, field, old_value, new_value);
+			repo.fi_revisions().insert(&self.revision(term, basis, year_month, field, old_value, new_value)).await?;
+		}
+		Ok(())
+	}
 }
 
+/// 재무 표의 항목 이름과 `FinancialInfo` 컬럼 이름
+const FI_PROPERTIES: [(&str, &str); 15] = [
+	("매출액", "sales"), ("영업이익", "profit"), ("당기순이익", "net_income"),
+	("영업이익률", "operating_margin"), ("순이익률", "net_margin"), ("ROE(지배주주)", "roe"),
+	("부채비율", "debt_ratio"), ("당좌비율", "quick_ratio"), ("유보율", "reserve_ratio"),
+	("EPS(원)", "eps"), ("PER(배)", "per"), ("BPS(원)", "bps"), ("PBR(배)", "pbr"),
+	("주당배당금(원)", "dividend"), ("시가배당률(%)", "dividend_yield"),
+];
+
 pub struct FinancialInfoRegistry {
 	stock_code: String,
 	pub list: Vec<FinancialInfo>,
@@ -72,7 +312,23 @@ impl FinancialInfoRegistry {
 		}
 	}
 
-	pub fn register(&mut self, year_month: YearMonth, data_name: &str, value: Option<f32>) {
+	/// `unit` 단위의 값을 항목의 저장 단위로 바꿔서 등록한다. 바꿀 수 없는 단위이면 등록하지 않는다
+	pub fn register(&mut self, year_month: YearMonth, data_name: &str, value: Option<Amount>, unit: Unit) {
+		let Some(field) = FinancialInfoRegistry::field_name(data_name) else {
+			return;
+		};
+		let target = FinancialInfo::unit_of(field).unwrap_or(unit);
+		let value = match value {
+			Some(v) => match unit.convert(v, target) {
+				Some(v) => Some(v.round_dp(FinancialInfo::FIGURE_SCALE)),
+				None => {
+					log::warn!("Can't convert `{data_name}` of `{}` from {} to {}", self.stock_code, unit.label(), target.label());
+					return;
+				}
+			},
+			None => None,
+		};
+
 		let fi = self.iter_mut().find(|fi| fi.year_month == year_month);
 		if let Some(fi) = fi {
 			FinancialInfoRegistry::set_fi_property(fi, data_name, value);
@@ -87,6 +343,13 @@ impl FinancialInfoRegistry {
 		}
 	}
 
+	/// 해당 기간의 연결/별도 구분을 정한다
+	pub fn set_basis(&mut self, year_month: YearMonth, basis: StatementBasis) {
+		for fi in self.iter_mut().filter(|fi| fi.year_month == year_month) {
+			fi.basis = basis;
+		}
+	}
+
 	pub fn iter(&self) -> impl Iterator<Item = &FinancialInfo> {
 		self.list.iter()
 	}
@@ -95,14 +358,18 @@ impl FinancialInfoRegistry {
 		self.list.iter_mut()
 	}
 
-	pub fn set_fi_property(fi: &mut FinancialInfo, data_name: &str, value: Option<f32>) {
-		match data_name {
-			"매출액" => fi.sales = value,
-			"영업이익" => fi.profit = value,
-			"당기순이익" => fi.net_income = value,
-			"주당배당금(원)" => fi.dividend = value,
-			"시가배당률(%)" => fi.dividend_yield = value,
-			_ => (),
+	pub fn is_fi_property(data_name: &str) -> bool {
+		FinancialInfoRegistry::field_name(data_name).is_some()
+	}
+
+	/// 재무 표의 항목 이름에 해당하는 `FinancialInfo` 컬럼 이름
+	pub fn field_name(data_name: &str) -> Option<&'static str> {
+		FI_PROPERTIES.iter().find(|(name, _)| *name == data_name).map(|(_, field)| *field)
+	}
+
+	pub fn set_fi_property(fi: &mut FinancialInfo, data_name: &str, value: Option<Amount>) {
+		if let Some(figure) = FinancialInfoRegistry::field_name(data_name).and_then(|field| fi.figure_mut(field)) {
+			*figure = value;
 		}
 	}
 
@@ -0,0 +1,97 @@
+use std::collections::{HashMap, HashSet};
+
+use chrono::NaiveDate;
+
+use crate::{entities::{EntityDao, ListingChange, ListingChangeKind, ProductItem}, types::{Error, Market}};
+
+/// 거래소 종목마스터 한 벌
+///
+/// `markets` 에 포함된 시장의 종목은 `items` 에 빠짐없이 들어 있어야 한다.
+/// 그래야 저장할 때 빠진 종목을 상장폐지로 판단할 수 있다.
+pub struct ItemMaster {
+	pub info_date: NaiveDate,
+	pub markets: Vec<Market>,
+	pub items: Vec<ProductItem>,
+}
+
+pub struct ItemMasterSummary {
+	pub upserted: usize,
+	pub listed: Vec<ListingChange>,
+	pub delisted: Vec<ListingChange>,
+}
+
+impl ItemMaster {
+	pub fn new(info_date: NaiveDate) -> Self {
+		Self {
+			info_date,
+			markets: Vec::new(),
+			items: Vec::new(),
+		}
+	}
+
+	pub fn extend(&mut self, markets: &[Market], items: Vec<ProductItem>) {
+		for market in markets {
+			if !self.markets.contains(market) {
+				self.markets.push(*market);
+			}
+		}
+		self.items.extend(items);
+	}
+
+	pub async fn save(&self, dao: &dyn EntityDao) -> Result<ItemMasterSummary, Error> {
+		let existing = dao.product_items().list(&self.markets).await?;
+
+		// 시장별로 직전에 반영된 종목마스터의 기준일자
+		let mut prev_info_dates = HashMap::<&'static str, NaiveDate>::new();
+		for item in existing.iter() {
+			let date = prev_info_dates.entry(item.market.as_str()).or_insert(item.info_date);
+			if *date < item.info_date {
+				*date = item.info_date;
+			}
+		}
+
+		let existing_codes: HashSet<&str> = existing.iter().map(|item| item.code.as_str()).collect();
+		let imported_codes: HashSet<&str> = self.items.iter().map(|item| item.code.as_str()).collect();
+
+		let mut listed = Vec::<ListingChange>::new();
+		for item in self.items.iter() {
+			dao.product_items().upsert(item).await?;
+
+			// 처음 적재하는 시장은 모든 종목이 신규상장으로 잡히므로 기록하지 않는다
+			if !existing_codes.contains(item.code.as_str()) && prev_info_dates.contains_key(item.market.as_str()) {
+				listed.push(self.listing_change(item, ListingChangeKind::Listed));
+			}
+		}
+
+		// 직전 종목마스터에는 있었지만 이번에 빠진 종목
+		let mut delisted = Vec::<ListingChange>::new();
+		for item in existing.iter().filter(|item| self.markets.contains(&item.market)) {
+			let Some(prev_info_date) = prev_info_dates.get(item.market.as_str()) else {
+				continue
+			};
+			if item.info_date == *prev_info_date && *prev_info_date < self.info_date && !imported_codes.contains(item.code.as_str()) {
+				delisted.push(self.listing_change(item, ListingChangeKind::Delisted));
+			}
+		}
+
+		for change in listed.iter().chain(delisted.iter()) {
+			dao.listing_changes().insert(change).await?;
+		}
+
+		Ok(ItemMasterSummary {
+			upserted: self.items.len(),
+			listed,
+			delisted,
+		})
+	}
+
+	fn listing_change(&self, item: &ProductItem, kind: ListingChangeKind) -> ListingChange {
+		ListingChange {
+			code: item.code.clone(),
+			change_date: self.info_date,
+			kind,
+			name: item.name.clone(),
+			market: item.market,
+		}
+	}
+}
@@ -4,3 +4,5 @@ pub mod entities;
 pub mod repository;
 pub mod data_source;
 pub mod fi_registry;
+pub mod item_master;
+pub mod analytics;
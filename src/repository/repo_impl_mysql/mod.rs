@@ -1,10 +1,19 @@
 mod repo;
 mod repo_tx;
 mod repo_stocks;
+mod repo_product_items;
+mod repo_listing_changes;
 mod repo_stock_prices;
 mod convert;
 mod repo_fi_annuals;
 mod repo_fi_quarters;
+mod repo_fi_estimates;
+mod repo_scrape_runs;
+mod repo_scrape_run_items;
+mod repo_fi_revisions;
+mod repo_fiscal_months;
+mod repo_balance_sheets;
+mod repo_cash_flows;
 
 pub use repo::RepoImpl;
 
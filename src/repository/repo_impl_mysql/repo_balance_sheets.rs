@@ -0,0 +1,132 @@
+use async_trait::async_trait;
+use chrono::NaiveDate;
+use mysql_async::{params, prelude::FromRow};
+use repo_helper::{SqlValues, database_table, mysql::{MySqlHelper, QueryObject}};
+use rust_decimal::Decimal;
+
+use crate::{entities::{BalanceSheet, BalanceSheetsDao}, types::{Error, FiTerm, YearMonth}};
+use crate::repository::repo_impl_mysql::{convert::IntoRepoResult, repo::RepoImpl, repo_tx::RepoTxImpl};
+
+
+#[async_trait]
+impl BalanceSheetsDao for RepoImpl {
+	async fn list(&self, stock_code: &str, term: FiTerm) -> Result<Vec<BalanceSheet>, Error> {
+		let mut q = self.get_query_object().await?;
+		list(&mut q, stock_code, term).await
+	}
+	async fn insert(&self, balance_sheet: &BalanceSheet, first_seen: NaiveDate) -> Result<(), Error> {
+		let mut q = self.get_query_object().await?;
+		insert(&mut q, balance_sheet, first_seen).await
+	}
+	async fn update(&self, balance_sheet: &BalanceSheet) -> Result<(), Error> {
+		let mut q = self.get_query_object().await?;
+		update(&mut q, balance_sheet).await
+	}
+}
+
+#[async_trait]
+impl BalanceSheetsDao for RepoTxImpl {
+	async fn list(&self, stock_code: &str, term: FiTerm) -> Result<Vec<BalanceSheet>, Error> {
+		let mut q = self.get_query_object().await?;
+		list(&mut q, stock_code, term).await
+	}
+	async fn insert(&self, balance_sheet: &BalanceSheet, first_seen: NaiveDate) -> Result<(), Error> {
+		let mut q = self.get_query_object().await?;
+		insert(&mut q, balance_sheet, first_seen).await
+	}
+	async fn update(&self, balance_sheet: &BalanceSheet) -> Result<(), Error> {
+		let mut q = self.get_query_object().await?;
+		update(&mut q, balance_sheet).await
+	}
+}
+
+
+database_table! {
+	#[table_name = "fi_balance_sheets", derive(FromRow)]
+	EntityRow {
+		stock_code: String,
+		term: String,
+		basis: String,
+		year: u16,
+		month: u8,
+		total_assets: Option<Decimal>,
+		total_liabilities: Option<Decimal>,
+		total_equity: Option<Decimal>,
+		owners_equity: Option<Decimal>,
+		cash: Option<Decimal>,
+		borrowings: Option<Decimal>,
+	}
+}
+impl TryFrom<EntityRow> for BalanceSheet {
+	type Error = Error;
+
+	fn try_from(value: EntityRow) -> Result<Self, Self::Error> {
+		Ok(Self {
+			stock_code: value.stock_code,
+			term: value.term.as_str().try_into()?,
+			basis: value.basis.as_str().try_into()?,
+			year_month: YearMonth::new(value.year, value.month),
+			total_assets: value.total_assets,
+			total_liabilities: value.total_liabilities,
+			total_equity: value.total_equity,
+			owners_equity: value.owners_equity,
+			cash: value.cash,
+			borrowings: value.borrowings,
+		})
+	}
+}
+
+
+const TABLE: &str = EntityRow::TABLE_NAME;
+const FIELDS: &str = EntityRow::TABLE_FIELDS;
+
+async fn list(q: &mut QueryObject<'_>, stock_code: &str, term: FiTerm) -> Result<Vec<BalanceSheet>, Error> {
+	let sql = format!("SELECT {FIELDS} FROM {TABLE} WHERE stock_code=:stock_code AND term=:term ORDER BY year, month, basis");
+	log::debug!("{sql} -- stock_code={stock_code}, term={}", term.as_str());
+
+	let stmt = q.prep(sql).await?;
+	let params = params! { stock_code, "term" => term.as_str() };
+	let rows: Vec<EntityRow> = q.exec(&stmt, params).await?;
+	rows.into_repo_result()
+}
+
+async fn insert(q: &mut QueryObject<'_>, balance_sheet: &BalanceSheet, first_seen: NaiveDate) -> Result<(), Error> {
+	let values = SqlValues::from(balance_sheet)
+		.with("first_seen", first_seen);
+	let insert_clause = values.with_named_binding_holder();
+	let sql = format!("INSERT INTO {TABLE} SET {insert_clause}");
+	log::debug!("{} -- {}", sql, values);
+
+	let stmt = q.prep(sql).await?;
+	q.exec_drop(stmt, values.params()).await?;
+	Ok(())
+}
+
+async fn update(q: &mut QueryObject<'_>, balance_sheet: &BalanceSheet) -> Result<(), Error> {
+	let values = SqlValues::from(balance_sheet);
+	let update_clause = values.with_named_binding_holder();
+	let sql = format!("UPDATE {TABLE} SET {update_clause} WHERE stock_code=:stock_code AND term=:term AND basis=:basis AND year=:year AND month=:month");
+	log::debug!("{} -- {}", sql, values);
+
+	let stmt = q.prep(sql).await?;
+	q.exec_drop(stmt, values.params()).await?;
+	Ok(())
+}
+
+
+impl<'a> From<&'a BalanceSheet> for SqlValues<'a> {
+	fn from(balance_sheet: &'a BalanceSheet) -> Self {
+		SqlValues::default()
+			.with("stock_code", &balance_sheet.stock_code)
+			.with("term", balance_sheet.term.as_str())
+			.with("basis", balance_sheet.basis.as_str())
+			.with("year", balance_sheet.year_month.year)
+			.with("month", balance_sheet.year_month.month)
+			.with("total_assets", balance_sheet.total_assets)
+			.with("total_liabilities", balance_sheet.total_liabilities)
+			.with("total_equity", balance_sheet.total_equity)
+			.with("owners_equity", balance_sheet.owners_equity)
+			.with("cash", balance_sheet.cash)
+			.with("borrowings", balance_sheet.borrowings)
+	}
+}
@@ -0,0 +1,132 @@
+use async_trait::async_trait;
+use chrono::NaiveDate;
+use mysql_async::{params, prelude::FromRow};
+use repo_helper::{SqlValues, database_table, mysql::{MySqlHelper, QueryObject}};
+use rust_decimal::Decimal;
+
+use crate::{entities::{CashFlow, CashFlowsDao}, types::{Error, FiTerm, YearMonth}};
+use crate::repository::repo_impl_mysql::{convert::IntoRepoResult, repo::RepoImpl, repo_tx::RepoTxImpl};
+
+
+#[async_trait]
+impl CashFlowsDao for RepoImpl {
+	async fn list(&self, stock_code: &str, term: FiTerm) -> Result<Vec<CashFlow>, Error> {
+		let mut q = self.get_query_object().await?;
+		list(&mut q, stock_code, term).await
+	}
+	async fn insert(&self, cash_flow: &CashFlow, first_seen: NaiveDate) -> Result<(), Error> {
+		let mut q = self.get_query_object().await?;
+		insert(&mut q, cash_flow, first_seen).await
+	}
+	async fn update(&self, cash_flow: &CashFlow) -> Result<(), Error> {
+		let mut q = self.get_query_object().await?;
+		update(&mut q, cash_flow).await
+	}
+}
+
+#[async_trait]
+impl CashFlowsDao for RepoTxImpl {
+	async fn list(&self, stock_code: &str, term: FiTerm) -> Result<Vec<CashFlow>, Error> {
+		let mut q = self.get_query_object().await?;
+		list(&mut q, stock_code, term).await
+	}
+	async fn insert(&self, cash_flow: &CashFlow, first_seen: NaiveDate) -> Result<(), Error> {
+		let mut q = self.get_query_object().await?;
+		insert(&mut q, cash_flow, first_seen).await
+	}
+	async fn update(&self, cash_flow: &CashFlow) -> Result<(), Error> {
+		let mut q = self.get_query_object().await?;
+		update(&mut q, cash_flow).await
+	}
+}
+
+
+database_table! {
+	#[table_name = "fi_cash_flows", derive(FromRow)]
+	EntityRow {
+		stock_code: String,
+		term: String,
+		basis: String,
+		year: u16,
+		month: u8,
+		operating: Option<Decimal>,
+		investing: Option<Decimal>,
+		financing: Option<Decimal>,
+		capex: Option<Decimal>,
+		fcf: Option<Decimal>,
+		cumulative: bool,
+	}
+}
+impl TryFrom<EntityRow> for CashFlow {
+	type Error = Error;
+
+	fn try_from(value: EntityRow) -> Result<Self, Self::Error> {
+		Ok(Self {
+			stock_code: value.stock_code,
+			term: value.term.as_str().try_into()?,
+			basis: value.basis.as_str().try_into()?,
+			year_month: YearMonth::new(value.year, value.month),
+			operating: value.operating,
+			investing: value.investing,
+			financing: value.financing,
+			capex: value.capex,
+			fcf: value.fcf,
+			cumulative: value.cumulative,
+		})
+	}
+}
+
+
+const TABLE: &str = EntityRow::TABLE_NAME;
+const FIELDS: &str = EntityRow::TABLE_FIELDS;
+
+async fn list(q: &mut QueryObject<'_>, stock_code: &str, term: FiTerm) -> Result<Vec<CashFlow>, Error> {
+	let sql = format!("SELECT {FIELDS} FROM {TABLE} WHERE stock_code=:stock_code AND term=:term ORDER BY year, month, basis");
+	log::debug!("{sql} -- stock_code={stock_code}, term={}", term.as_str());
+
+	let stmt = q.prep(sql).await?;
+	let params = params! { stock_code, "term" => term.as_str() };
+	let rows: Vec<EntityRow> = q.exec(&stmt, params).await?;
+	rows.into_repo_result()
+}
+
+async fn insert(q: &mut QueryObject<'_>, cash_flow: &CashFlow, first_seen: NaiveDate) -> Result<(), Error> {
+	let values = SqlValues::from(cash_flow)
+		.with("first_seen", first_seen);
+	let insert_clause = values.with_named_binding_holder();
+	let sql = format!("INSERT INTO {TABLE} SET {insert_clause}");
+	log::debug!("{} -- {}", sql, values);
+
+	let stmt = q.prep(sql).await?;
+	q.exec_drop(stmt, values.params()).await?;
+	Ok(())
+}
+
+async fn update(q: &mut QueryObject<'_>, cash_flow: &CashFlow) -> Result<(), Error> {
+	let values = SqlValues::from(cash_flow);
+	let update_clause = values.with_named_binding_holder();
+	let sql = format!("UPDATE {TABLE} SET {update_clause} WHERE stock_code=:stock_code AND term=:term AND basis=:basis AND year=:year AND month=:month");
+	log::debug!("{} -- {}", sql, values);
+
+	let stmt = q.prep(sql).await?;
+	q.exec_drop(stmt, values.params()).await?;
+	Ok(())
+}
+
+
+impl<'a> From<&'a CashFlow> for SqlValues<'a> {
+	fn from(cash_flow: &'a CashFlow) -> Self {
+		SqlValues::default()
+			.with("stock_code", &cash_flow.stock_code)
+			.with("term", cash_flow.term.as_str())
+			.with("basis", cash_flow.basis.as_str())
+			.with("year", cash_flow.year_month.year)
+			.with("month", cash_flow.year_month.month)
+			.with("operating", cash_flow.operating)
+			.with("investing", cash_flow.investing)
+			.with("financing", cash_flow.financing)
+			.with("capex", cash_flow.capex)
+			.with("fcf", cash_flow.fcf)
+			.with("cumulative", cash_flow.cumulative)
+	}
+}
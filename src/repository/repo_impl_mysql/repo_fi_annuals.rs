@@ -1,49 +1,67 @@
 use async_trait::async_trait;
+use chrono::NaiveDate;
 use mysql_async::{params, prelude::FromRow};
 use repo_helper::{SqlValues, database_table, mysql::{MySqlHelper, QueryObject}};
+use rust_decimal::Decimal;
 
-use crate::{entities::{FiAnnualData, FiAnnualsDao, FinancialInfo}, types::{Error, YearMonth}};
-use crate::repository::repo_impl_mysql::{convert::IntoRepoResult, repo::RepoImpl, repo_tx::RepoTxImpl};
+use crate::{entities::{FiAnnualData, FiAnnualsDao, FiRevision, FinancialInfo}, types::{Error, FiTerm, StatementBasis, YearMonth}};
+use crate::repository::repo_impl_mysql::{convert::IntoRepoResult, repo::RepoImpl, repo_fi_revisions, repo_tx::RepoTxImpl};
 
 
 #[async_trait]
 impl FiAnnualsDao for RepoImpl {
-	async fn find(&self, stock_code: &str, year: u16) -> Result<Option<FinancialInfo>, Error> {
+	async fn find(&self, stock_code: &str, basis: StatementBasis, year: u16) -> Result<Option<FinancialInfo>, Error> {
 		let mut q = self.get_query_object().await?;
-		find(&mut q, stock_code, year).await
+		find(&mut q, stock_code, basis, year).await
 	}
 	async fn list(&self, stock_code: &str) -> Result<Vec<FinancialInfo>, Error> {
 		let mut q = self.get_query_object().await?;
 		list(&mut q, stock_code).await
 	}
-	async fn insert(&self, annual: &FinancialInfo) -> Result<(), Error> {
+	async fn insert(&self, annual: &FinancialInfo, first_seen: NaiveDate) -> Result<(), Error> {
 		let mut q = self.get_query_object().await?;
-		insert(&mut q, annual).await
+		insert(&mut q, annual, first_seen).await
 	}
 	async fn update(&self, annual: &mut FinancialInfo, data: FiAnnualData) -> Result<(), Error> {
 		let mut q = self.get_query_object().await?;
 		update(&mut q, annual, data).await
 	}
+	async fn list_known_on(&self, stock_code: &str, date: NaiveDate) -> Result<Vec<FinancialInfo>, Error> {
+		let mut q = self.get_query_object().await?;
+		list_known_on(&mut q, stock_code, date).await
+	}
+	async fn list_as_of(&self, stock_code: &str, date: NaiveDate) -> Result<Vec<FinancialInfo>, Error> {
+		let mut q = self.get_query_object().await?;
+		list_as_of(&mut q, stock_code, date).await
+	}
 }
 
 #[async_trait]
 impl FiAnnualsDao for RepoTxImpl {
-	async fn find(&self, stock_code: &str, year: u16) -> Result<Option<FinancialInfo>, Error> {
+	async fn find(&self, stock_code: &str, basis: StatementBasis, year: u16) -> Result<Option<FinancialInfo>, Error> {
 		let mut q = self.get_query_object().await?;
-		find(&mut q, stock_code, year).await
+		find(&mut q, stock_code, basis, year).await
 	}
 	async fn list(&self, stock_code: &str) -> Result<Vec<FinancialInfo>, Error> {
 		let mut q = self.get_query_object().await?;
 		list(&mut q, stock_code).await
 	}
-	async fn insert(&self, annual: &FinancialInfo) -> Result<(), Error> {
+	async fn insert(&self, annual: &FinancialInfo, first_seen: NaiveDate) -> Result<(), Error> {
 		let mut q = self.get_query_object().await?;
-		insert(&mut q, annual).await
+		insert(&mut q, annual, first_seen).await
 	}
 	async fn update(&self, annual: &mut FinancialInfo, data: FiAnnualData) -> Result<(), Error> {
 		let mut q = self.get_query_object().await?;
 		update(&mut q, annual, data).await
 	}
+	async fn list_known_on(&self, stock_code: &str, date: NaiveDate) -> Result<Vec<FinancialInfo>, Error> {
+		let mut q = self.get_query_object().await?;
+		list_known_on(&mut q, stock_code, date).await
+	}
+	async fn list_as_of(&self, stock_code: &str, date: NaiveDate) -> Result<Vec<FinancialInfo>, Error> {
+		let mut q = self.get_query_object().await?;
+		list_as_of(&mut q, stock_code, date).await
+	}
 }
 
 
@@ -51,13 +69,24 @@ database_table! {
 	#[table_name = "fi_annuals", derive(FromRow)]
 	EntityRow {
 		stock_code: String,
+		basis: String,
 		year: u16,
 		month: u8,
-		sales: Option<f32>,
-		profit: Option<f32>,
-		net_income: Option<f32>,
-		dividend: Option<f32>,
-		dividend_yield: Option<f32>,
+		sales: Option<Decimal>,
+		profit: Option<Decimal>,
+		net_income: Option<Decimal>,
+		operating_margin: Option<Decimal>,
+		net_margin: Option<Decimal>,
+		roe: Option<Decimal>,
+		debt_ratio: Option<Decimal>,
+		quick_ratio: Option<Decimal>,
+		reserve_ratio: Option<Decimal>,
+		eps: Option<Decimal>,
+		per: Option<Decimal>,
+		bps: Option<Decimal>,
+		pbr: Option<Decimal>,
+		dividend: Option<Decimal>,
+		dividend_yield: Option<Decimal>,
 	}
 }
 impl TryFrom<EntityRow> for FinancialInfo {
@@ -66,12 +95,24 @@ impl TryFrom<EntityRow> for FinancialInfo {
 	fn try_from(value: EntityRow) -> Result<Self, Self::Error> {
 		Ok(Self {
 			stock_code: value.stock_code,
+			basis: value.basis.as_str().try_into()?,
 			year_month: YearMonth::new(value.year, value.month),
 			sales: value.sales,
 			profit: value.profit,
 			net_income: value.net_income,
+			operating_margin: value.operating_margin,
+			net_margin: value.net_margin,
+			roe: value.roe,
+			debt_ratio: value.debt_ratio,
+			quick_ratio: value.quick_ratio,
+			reserve_ratio: value.reserve_ratio,
+			eps: value.eps,
+			per: value.per,
+			bps: value.bps,
+			pbr: value.pbr,
 			dividend: value.dividend,
 			dividend_yield: value.dividend_yield,
+			derived: false,
 		})
 	}
 }
@@ -80,19 +121,20 @@ impl TryFrom<EntityRow> for FinancialInfo {
 const TABLE: &str = EntityRow::TABLE_NAME;
 const FIELDS: &str = EntityRow::TABLE_FIELDS;
 
-async fn find(q: &mut QueryObject<'_>, stock_code: &str, year: u16) -> Result<Option<FinancialInfo>, Error> {
-	let sql = format!("SELECT {FIELDS} FROM {TABLE} WHERE stock_code=:stock_code AND year=:year");
-	log::debug!("{sql} -- stock_code={stock_code}, year={year}");
+async fn find(q: &mut QueryObject<'_>, stock_code: &str, basis: StatementBasis, year: u16) -> Result<Option<FinancialInfo>, Error> {
+	// 결산월을 바꾼 해에는 두 개의 연간 실적이 있을 수 있다. 나중 것을 돌려준다
+	let sql = format!("SELECT {FIELDS} FROM {TABLE} WHERE stock_code=:stock_code AND basis=:basis AND year=:year ORDER BY month DESC LIMIT 1");
+	log::debug!("{sql} -- stock_code={stock_code}, basis={}, year={year}", basis.as_str());
 
 	let stmt = q.prep(sql).await?;
-	let params = params! { stock_code, year };
+	let params = params! { stock_code, "basis" => basis.as_str(), year };
 	let ent: Option<EntityRow> = q.exec_first(&stmt, params).await?;
 	let fi = ent.map(FinancialInfo::try_from).transpose()?;
 	Ok(fi)
 }
 
 async fn list(q: &mut QueryObject<'_>, stock_code: &str) -> Result<Vec<FinancialInfo>, Error> {
-	let sql = format!("SELECT {FIELDS} FROM {TABLE} WHERE stock_code=:stock_code ORDER BY year");
+	let sql = format!("SELECT {FIELDS} FROM {TABLE} WHERE stock_code=:stock_code ORDER BY year, month, basis");
 	log::debug!("{sql} -- {{stock_code={stock_code}}}");
 
 	let stmt = q.prep(sql).await?;
@@ -101,8 +143,30 @@ async fn list(q: &mut QueryObject<'_>, stock_code: &str) -> Result<Vec<Financial
 	rows.into_repo_result()
 }
 
-async fn insert(q: &mut QueryObject<'_>, annual: &FinancialInfo) -> Result<(), Error> {
-	let values = SqlValues::from(annual);
+async fn list_known_on(q: &mut QueryObject<'_>, stock_code: &str, date: NaiveDate) -> Result<Vec<FinancialInfo>, Error> {
+	let mut list = list(q, stock_code).await?;
+	let revisions = repo_fi_revisions::list_after(q, stock_code, FiTerm::Annual, date).await?;
+	FiRevision::revert_after(&mut list, FiTerm::Annual, &revisions, date);
+	Ok(list)
+}
+
+async fn list_as_of(q: &mut QueryObject<'_>, stock_code: &str, date: NaiveDate) -> Result<Vec<FinancialInfo>, Error> {
+	let sql = format!("SELECT {FIELDS} FROM {TABLE} WHERE stock_code=:stock_code AND first_seen<=:date ORDER BY year, month, basis");
+	log::debug!("{sql} -- stock_code={stock_code}, date={date}");
+
+	let stmt = q.prep(sql).await?;
+	let params = params! { stock_code, date };
+	let rows: Vec<EntityRow> = q.exec(&stmt, params).await?;
+	let mut list: Vec<FinancialInfo> = rows.into_repo_result()?;
+
+	let revisions = repo_fi_revisions::list_after(q, stock_code, FiTerm::Annual, date).await?;
+	FiRevision::revert_after(&mut list, FiTerm::Annual, &revisions, date);
+	Ok(list)
+}
+
+async fn insert(q: &mut QueryObject<'_>, annual: &FinancialInfo, first_seen: NaiveDate) -> Result<(), Error> {
+	let values = SqlValues::from(annual)
+		.with("first_seen", first_seen);
 	let insert_clause = values.with_named_binding_holder();
 	let sql = format!("INSERT INTO {TABLE} SET {insert_clause}");
 	log::debug!("{} -- {}", sql, values);
@@ -115,12 +179,14 @@ async fn insert(q: &mut QueryObject<'_>, annual: &FinancialInfo) -> Result<(), E
 async fn update(q: &mut QueryObject<'_>, annual: &mut FinancialInfo, data: FiAnnualData) -> Result<(), Error> {
 	let values = SqlValues::from(&data);
 	let insert_clause = values.with_named_binding_holder();
-	let sql = format!("UPDATE {TABLE} SET {insert_clause} WHERE stock_code=:stock_code AND year=:year");
-	log::debug!("{} -- {}, stock_code={:?}, year={}", sql, values, annual.stock_code, annual.year_month.year);
+	let sql = format!("UPDATE {TABLE} SET {insert_clause} WHERE stock_code=:stock_code AND basis=:basis AND year=:year AND month=:old_month");
+	log::debug!("{} -- {}, stock_code={:?}, basis={}, year={}, old_month={}", sql, values, annual.stock_code, annual.basis.as_str(), annual.year_month.year, annual.year_month.month);
 
 	let mut params = values.params();
 	params.push(("stock_code".into(), annual.stock_code.to_owned().into()));
+	params.push(("basis".into(), annual.basis.as_str().into()));
 	params.push(("year".into(), annual.year_month.year.into()));
+	params.push(("old_month".into(), annual.year_month.month.into()));
 
 	let stmt = q.prep(sql).await?;
 	q.exec_drop(stmt, params).await?;
@@ -129,6 +195,16 @@ async fn update(q: &mut QueryObject<'_>, annual: &mut FinancialInfo, data: FiAnn
 	annual.sales = data.sales;
 	annual.profit = data.profit;
 	annual.net_income = data.net_income;
+	annual.operating_margin = data.operating_margin;
+	annual.net_margin = data.net_margin;
+	annual.roe = data.roe;
+	annual.debt_ratio = data.debt_ratio;
+	annual.quick_ratio = data.quick_ratio;
+	annual.reserve_ratio = data.reserve_ratio;
+	annual.eps = data.eps;
+	annual.per = data.per;
+	annual.bps = data.bps;
+	annual.pbr = data.pbr;
 	annual.dividend = data.dividend;
 	annual.dividend_yield = data.dividend_yield;
 	Ok(())
@@ -139,11 +215,22 @@ impl<'a> From<&'a FinancialInfo> for SqlValues<'a> {
 	fn from(fi: &'a FinancialInfo) -> Self {
 		SqlValues::default()
 			.with("stock_code", &fi.stock_code)
+			.with("basis", fi.basis.as_str())
 			.with("year", fi.year_month.year)
 			.with("month", fi.year_month.month)
 			.with("sales", fi.sales)
 			.with("profit", fi.profit)
 			.with("net_income", fi.net_income)
+			.with("operating_margin", fi.operating_margin)
+			.with("net_margin", fi.net_margin)
+			.with("roe", fi.roe)
+			.with("debt_ratio", fi.debt_ratio)
+			.with("quick_ratio", fi.quick_ratio)
+			.with("reserve_ratio", fi.reserve_ratio)
+			.with("eps", fi.eps)
+			.with("per", fi.per)
+			.with("bps", fi.bps)
+			.with("pbr", fi.pbr)
 			.with("dividend", fi.dividend)
 			.with("dividend_yield", fi.dividend_yield)
 	}
@@ -156,6 +243,16 @@ impl<'a> From<&'a FiAnnualData> for SqlValues<'a> {
 			.with("sales", data.sales)
 			.with("profit", data.profit)
 			.with("net_income", data.net_income)
+			.with("operating_margin", data.operating_margin)
+			.with("net_margin", data.net_margin)
+			.with("roe", data.roe)
+			.with("debt_ratio", data.debt_ratio)
+			.with("quick_ratio", data.quick_ratio)
+			.with("reserve_ratio", data.reserve_ratio)
+			.with("eps", data.eps)
+			.with("per", data.per)
+			.with("bps", data.bps)
+			.with("pbr", data.pbr)
 			.with("dividend", data.dividend)
 			.with("dividend_yield", data.dividend_yield)
 	}
@@ -0,0 +1,162 @@
+use async_trait::async_trait;
+use chrono::NaiveDate;
+use mysql_async::{params, prelude::FromRow};
+use repo_helper::{SqlValues, database_table, mysql::{MySqlHelper, QueryObject}};
+use rust_decimal::Decimal;
+
+use crate::{entities::{FiEstimate, FiEstimatesDao, FinancialInfo}, types::{Error, StatementBasis, YearMonth}};
+use crate::repository::repo_impl_mysql::{convert::IntoRepoResult, repo::RepoImpl, repo_tx::RepoTxImpl};
+
+
+#[async_trait]
+impl FiEstimatesDao for RepoImpl {
+	async fn list(&self, stock_code: &str) -> Result<Vec<FiEstimate>, Error> {
+		let mut q = self.get_query_object().await?;
+		list(&mut q, stock_code).await
+	}
+	async fn latest(&self, stock_code: &str) -> Result<Vec<FiEstimate>, Error> {
+		let mut q = self.get_query_object().await?;
+		latest(&mut q, stock_code).await
+	}
+	async fn latest_as_of(&self, stock_code: &str, date: NaiveDate) -> Result<Vec<FiEstimate>, Error> {
+		let mut q = self.get_query_object().await?;
+		latest_as_of(&mut q, stock_code, date).await
+	}
+	async fn upsert(&self, estimate: &FiEstimate) -> Result<(), Error> {
+		let mut q = self.get_query_object().await?;
+		upsert(&mut q, estimate).await
+	}
+}
+
+#[async_trait]
+impl FiEstimatesDao for RepoTxImpl {
+	async fn list(&self, stock_code: &str) -> Result<Vec<FiEstimate>, Error> {
+		let mut q = self.get_query_object().await?;
+		list(&mut q, stock_code).await
+	}
+	async fn latest(&self, stock_code: &str) -> Result<Vec<FiEstimate>, Error> {
+		let mut q = self.get_query_object().await?;
+		latest(&mut q, stock_code).await
+	}
+	async fn latest_as_of(&self, stock_code: &str, date: NaiveDate) -> Result<Vec<FiEstimate>, Error> {
+		let mut q = self.get_query_object().await?;
+		latest_as_of(&mut q, stock_code, date).await
+	}
+	async fn upsert(&self, estimate: &FiEstimate) -> Result<(), Error> {
+		let mut q = self.get_query_object().await?;
+		upsert(&mut q, estimate).await
+	}
+}
+
+
+database_table! {
+	#[table_name = "fi_estimates", derive(FromRow)]
+	EntityRow {
+		stock_code: String,
+		term: String,
+		year: u16,
+		month: u8,
+		capture_date: NaiveDate,
+		sales: Option<Decimal>,
+		profit: Option<Decimal>,
+		net_income: Option<Decimal>,
+		operating_margin: Option<Decimal>,
+		net_margin: Option<Decimal>,
+		roe: Option<Decimal>,
+		debt_ratio: Option<Decimal>,
+		quick_ratio: Option<Decimal>,
+		reserve_ratio: Option<Decimal>,
+		eps: Option<Decimal>,
+		per: Option<Decimal>,
+		bps: Option<Decimal>,
+		pbr: Option<Decimal>,
+		dividend: Option<Decimal>,
+		dividend_yield: Option<Decimal>,
+	}
+}
+impl TryFrom<EntityRow> for FiEstimate {
+	type Error = Error;
+
+	fn try_from(value: EntityRow) -> Result<Self, Self::Error> {
+		Ok(Self {
+			term: value.term.as_str().try_into()?,
+			capture_date: value.capture_date,
+			info: FinancialInfo {
+				stock_code: value.stock_code,
+				year_month: YearMonth::new(value.year, value.month),
+				// 추정실적은 연결/별도 구분을 저장하지 않는다
+				basis: StatementBasis::default(),
+				sales: value.sales,
+				profit: value.profit,
+				net_income: value.net_income,
+				operating_margin: value.operating_margin,
+				net_margin: value.net_margin,
+				roe: value.roe,
+				debt_ratio: value.debt_ratio,
+				quick_ratio: value.quick_ratio,
+				reserve_ratio: value.reserve_ratio,
+				eps: value.eps,
+				per: value.per,
+				bps: value.bps,
+				pbr: value.pbr,
+				dividend: value.dividend,
+				dividend_yield: value.dividend_yield,
+				derived: false,
+			},
+		})
+	}
+}
+
+
+const TABLE: &str = EntityRow::TABLE_NAME;
+const FIELDS: &str = EntityRow::TABLE_FIELDS;
+
+async fn list(q: &mut QueryObject<'_>, stock_code: &str) -> Result<Vec<FiEstimate>, Error> {
+	let sql = format!("SELECT {FIELDS} FROM {TABLE} WHERE stock_code=:stock_code ORDER BY capture_date, term, year, month");
+	log::debug!("{sql} -- {{stock_code={stock_code}}}");
+
+	let stmt = q.prep(sql).await?;
+	let params = params! { stock_code };
+	let rows: Vec<EntityRow> = q.exec(&stmt, params).await?;
+	rows.into_repo_result()
+}
+
+async fn latest(q: &mut QueryObject<'_>, stock_code: &str) -> Result<Vec<FiEstimate>, Error> {
+	let sql = format!("SELECT {FIELDS} FROM {TABLE} WHERE stock_code=:stock_code AND capture_date=(SELECT MAX(capture_date) FROM {TABLE} WHERE stock_code=:stock_code) ORDER BY term, year, month");
+	log::debug!("{sql} -- {{stock_code={stock_code}}}");
+
+	let stmt = q.prep(sql).await?;
+	let params = params! { stock_code };
+	let rows: Vec<EntityRow> = q.exec(&stmt, params).await?;
+	rows.into_repo_result()
+}
+
+async fn latest_as_of(q: &mut QueryObject<'_>, stock_code: &str, date: NaiveDate) -> Result<Vec<FiEstimate>, Error> {
+	let sql = format!("SELECT {FIELDS} FROM {TABLE} WHERE stock_code=:stock_code AND capture_date=(SELECT MAX(capture_date) FROM {TABLE} WHERE stock_code=:stock_code AND capture_date<=:date) ORDER BY term, year, month");
+	log::debug!("{sql} -- stock_code={stock_code}, date={date}");
+
+	let stmt = q.prep(sql).await?;
+	let params = params! { stock_code, date };
+	let rows: Vec<EntityRow> = q.exec(&stmt, params).await?;
+	rows.into_repo_result()
+}
+
+async fn upsert(q: &mut QueryObject<'_>, estimate: &FiEstimate) -> Result<(), Error> {
+	let values = SqlValues::from(estimate);
+	let insert_clause = values.with_named_binding_holder();
+	let sql = format!("INSERT INTO {TABLE} SET {insert_clause} ON DUPLICATE KEY UPDATE {insert_clause}");
+	log::debug!("{} -- {}", sql, values);
+
+	let stmt = q.prep(sql).await?;
+	q.exec_drop(stmt, values.params()).await?;
+	Ok(())
+}
+
+
+impl<'a> From<&'a FiEstimate> for SqlValues<'a> {
+	fn from(estimate: &'a FiEstimate) -> Self {
+		SqlValues::from(&estimate.info)
+			.with("term", estimate.term.as_str())
+			.with("capture_date", estimate.capture_date)
+	}
+}
@@ -1,49 +1,67 @@
 use async_trait::async_trait;
+use chrono::NaiveDate;
 use mysql_async::{params, prelude::FromRow};
 use repo_helper::{SqlValues, database_table, mysql::{MySqlHelper, QueryObject}};
+use rust_decimal::Decimal;
 
-use crate::{entities::{FiQuarterData, FiQuartersDao, FinancialInfo}, types::{Error, YearMonth}};
-use crate::repository::repo_impl_mysql::{convert::IntoRepoResult, repo::RepoImpl, repo_tx::RepoTxImpl};
+use crate::{entities::{FiQuarterData, FiQuartersDao, FiRevision, FinancialInfo}, types::{Error, FiTerm, StatementBasis, YearMonth}};
+use crate::repository::repo_impl_mysql::{convert::IntoRepoResult, repo::RepoImpl, repo_fi_revisions, repo_tx::RepoTxImpl};
 
 
 #[async_trait]
 impl FiQuartersDao for RepoImpl {
-	async fn find(&self, stock_code: &str, year: u16, month: u8) -> Result<Option<FinancialInfo>, Error> {
+	async fn find(&self, stock_code: &str, basis: StatementBasis, year: u16, month: u8) -> Result<Option<FinancialInfo>, Error> {
 		let mut q = self.get_query_object().await?;
-		find(&mut q, stock_code, year, month).await
+		find(&mut q, stock_code, basis, year, month).await
 	}
 	async fn list(&self, stock_code: &str) -> Result<Vec<FinancialInfo>, Error> {
 		let mut q = self.get_query_object().await?;
 		list(&mut q, stock_code).await
 	}
-	async fn insert(&self, annual: &FinancialInfo) -> Result<(), Error> {
+	async fn insert(&self, annual: &FinancialInfo, first_seen: NaiveDate) -> Result<(), Error> {
 		let mut q = self.get_query_object().await?;
-		insert(&mut q, annual).await
+		insert(&mut q, annual, first_seen).await
 	}
 	async fn update(&self, annual: &mut FinancialInfo, data: FiQuarterData) -> Result<(), Error> {
 		let mut q = self.get_query_object().await?;
 		update(&mut q, annual, data).await
 	}
+	async fn list_known_on(&self, stock_code: &str, date: NaiveDate) -> Result<Vec<FinancialInfo>, Error> {
+		let mut q = self.get_query_object().await?;
+		list_known_on(&mut q, stock_code, date).await
+	}
+	async fn list_as_of(&self, stock_code: &str, date: NaiveDate) -> Result<Vec<FinancialInfo>, Error> {
+		let mut q = self.get_query_object().await?;
+		list_as_of(&mut q, stock_code, date).await
+	}
 }
 
 #[async_trait]
 impl FiQuartersDao for RepoTxImpl {
-	async fn find(&self, stock_code: &str, year: u16, month: u8) -> Result<Option<FinancialInfo>, Error> {
+	async fn find(&self, stock_code: &str, basis: StatementBasis, year: u16, month: u8) -> Result<Option<FinancialInfo>, Error> {
 		let mut q = self.get_query_object().await?;
-		find(&mut q, stock_code, year, month).await
+		find(&mut q, stock_code, basis, year, month).await
 	}
 	async fn list(&self, stock_code: &str) -> Result<Vec<FinancialInfo>, Error> {
 		let mut q = self.get_query_object().await?;
 		list(&mut q, stock_code).await
 	}
-	async fn insert(&self, annual: &FinancialInfo) -> Result<(), Error> {
+	async fn insert(&self, annual: &FinancialInfo, first_seen: NaiveDate) -> Result<(), Error> {
 		let mut q = self.get_query_object().await?;
-		insert(&mut q, annual).await
+		insert(&mut q, annual, first_seen).await
 	}
 	async fn update(&self, annual: &mut FinancialInfo, data: FiQuarterData) -> Result<(), Error> {
 		let mut q = self.get_query_object().await?;
 		update(&mut q, annual, data).await
 	}
+	async fn list_known_on(&self, stock_code: &str, date: NaiveDate) -> Result<Vec<FinancialInfo>, Error> {
+		let mut q = self.get_query_object().await?;
+		list_known_on(&mut q, stock_code, date).await
+	}
+	async fn list_as_of(&self, stock_code: &str, date: NaiveDate) -> Result<Vec<FinancialInfo>, Error> {
+		let mut q = self.get_query_object().await?;
+		list_as_of(&mut q, stock_code, date).await
+	}
 }
 
 
@@ -51,13 +69,25 @@ database_table! {
 	#[table_name = "fi_quarters", derive(FromRow)]
 	EntityRow {
 		stock_code: String,
+		basis: String,
 		year: u16,
 		month: u8,
-		sales: Option<f32>,
-		profit: Option<f32>,
-		net_income: Option<f32>,
-		dividend: Option<f32>,
-		dividend_yield: Option<f32>,
+		sales: Option<Decimal>,
+		profit: Option<Decimal>,
+		net_income: Option<Decimal>,
+		operating_margin: Option<Decimal>,
+		net_margin: Option<Decimal>,
+		roe: Option<Decimal>,
+		debt_ratio: Option<Decimal>,
+		quick_ratio: Option<Decimal>,
+		reserve_ratio: Option<Decimal>,
+		eps: Option<Decimal>,
+		per: Option<Decimal>,
+		bps: Option<Decimal>,
+		pbr: Option<Decimal>,
+		dividend: Option<Decimal>,
+		dividend_yield: Option<Decimal>,
+		derived: bool,
 	}
 }
 impl TryFrom<EntityRow> for FinancialInfo {
@@ -66,12 +96,24 @@ impl TryFrom<EntityRow> for FinancialInfo {
 	fn try_from(value: EntityRow) -> Result<Self, Self::Error> {
 		Ok(Self {
 			stock_code: value.stock_code,
+			basis: value.basis.as_str().try_into()?,
 			year_month: YearMonth::new(value.year, value.month),
 			sales: value.sales,
 			profit: value.profit,
 			net_income: value.net_income,
+			operating_margin: value.operating_margin,
+			net_margin: value.net_margin,
+			roe: value.roe,
+			debt_ratio: value.debt_ratio,
+			quick_ratio: value.quick_ratio,
+			reserve_ratio: value.reserve_ratio,
+			eps: value.eps,
+			per: value.per,
+			bps: value.bps,
+			pbr: value.pbr,
 			dividend: value.dividend,
 			dividend_yield: value.dividend_yield,
+			derived: value.derived,
 		})
 	}
 }
@@ -80,19 +122,19 @@ impl TryFrom<EntityRow> for FinancialInfo {
 const TABLE: &str = EntityRow::TABLE_NAME;
 const FIELDS: &str = EntityRow::TABLE_FIELDS;
 
-async fn find(q: &mut QueryObject<'_>, stock_code: &str, year: u16, month: u8) -> Result<Option<FinancialInfo>, Error> {
-	let sql = format!("SELECT {FIELDS} FROM {TABLE} WHERE stock_code=:stock_code AND year=:year AND month=:month");
-	log::debug!("{sql} -- stock_code={stock_code}, year={year}, month={month}");
+async fn find(q: &mut QueryObject<'_>, stock_code: &str, basis: StatementBasis, year: u16, month: u8) -> Result<Option<FinancialInfo>, Error> {
+	let sql = format!("SELECT {FIELDS} FROM {TABLE} WHERE stock_code=:stock_code AND basis=:basis AND year=:year AND month=:month");
+	log::debug!("{sql} -- stock_code={stock_code}, basis={}, year={year}, month={month}", basis.as_str());
 
 	let stmt = q.prep(sql).await?;
-	let params = params! { stock_code, year };
+	let params = params! { stock_code, "basis" => basis.as_str(), year, month };
 	let ent: Option<EntityRow> = q.exec_first(&stmt, params).await?;
 	let fi = ent.map(FinancialInfo::try_from).transpose()?;
 	Ok(fi)
 }
 
 async fn list(q: &mut QueryObject<'_>, stock_code: &str) -> Result<Vec<FinancialInfo>, Error> {
-	let sql = format!("SELECT {FIELDS} FROM {TABLE} WHERE stock_code=:stock_code ORDER BY year");
+	let sql = format!("SELECT {FIELDS} FROM {TABLE} WHERE stock_code=:stock_code ORDER BY year, month, basis");
 	log::debug!("{sql} -- {{stock_code={stock_code}}}");
 
 	let stmt = q.prep(sql).await?;
@@ -101,8 +143,31 @@ async fn list(q: &mut QueryObject<'_>, stock_code: &str) -> Result<Vec<Financial
 	rows.into_repo_result()
 }
 
-async fn insert(q: &mut QueryObject<'_>, quarter: &FinancialInfo) -> Result<(), Error> {
-	let values = SqlValues::from(quarter);
+async fn list_known_on(q: &mut QueryObject<'_>, stock_code: &str, date: NaiveDate) -> Result<Vec<FinancialInfo>, Error> {
+	let mut list = list(q, stock_code).await?;
+	let revisions = repo_fi_revisions::list_after(q, stock_code, FiTerm::Quarter, date).await?;
+	FiRevision::revert_after(&mut list, FiTerm::Quarter, &revisions, date);
+	Ok(list)
+}
+
+async fn list_as_of(q: &mut QueryObject<'_>, stock_code: &str, date: NaiveDate) -> Result<Vec<FinancialInfo>, Error> {
+	let sql = format!("SELECT {FIELDS} FROM {TABLE} WHERE stock_code=:stock_code AND first_seen<=:date ORDER BY year, month, basis");
+	log::debug!("{sql} -- stock_code={stock_code}, date={date}");
+
+	let stmt = q.prep(sql).await?;
+	let params = params! { stock_code, date };
+	let rows: Vec<EntityRow> = q.exec(&stmt, params).await?;
+	let mut list: Vec<FinancialInfo> = rows.into_repo_result()?;
+
+	let revisions = repo_fi_revisions::list_after(q, stock_code, FiTerm::Quarter, date).await?;
+	FiRevision::revert_after(&mut list, FiTerm::Quarter, &revisions, date);
+	Ok(list)
+}
+
+async fn insert(q: &mut QueryObject<'_>, quarter: &FinancialInfo, first_seen: NaiveDate) -> Result<(), Error> {
+	let values = SqlValues::from(quarter)
+		.with("derived", quarter.derived)
+		.with("first_seen", first_seen);
 	let insert_clause = values.with_named_binding_holder();
 	let sql = format!("INSERT INTO {TABLE} SET {insert_clause}");
 	log::debug!("{} -- {}", sql, values);
@@ -115,11 +180,12 @@ async fn insert(q: &mut QueryObject<'_>, quarter: &FinancialInfo) -> Result<(),
 async fn update(q: &mut QueryObject<'_>, quarter: &mut FinancialInfo, data: FiQuarterData) -> Result<(), Error> {
 	let values = SqlValues::from(&data);
 	let insert_clause = values.with_named_binding_holder();
-	let sql = format!("UPDATE {TABLE} SET {insert_clause} WHERE stock_code=:stock_code AND year=:year AND month=:month");
-	log::debug!("{} -- {}, stock_code={}, year={}, month={}", sql, values, quarter.stock_code, quarter.year_month.year, quarter.year_month.month);
+	let sql = format!("UPDATE {TABLE} SET {insert_clause} WHERE stock_code=:stock_code AND basis=:basis AND year=:year AND month=:month");
+	log::debug!("{} -- {}, stock_code={}, basis={}, year={}, month={}", sql, values, quarter.stock_code, quarter.basis.as_str(), quarter.year_month.year, quarter.year_month.month);
 
 	let mut params = values.params();
 	params.push(("stock_code".into(), quarter.stock_code.to_owned().into()));
+	params.push(("basis".into(), quarter.basis.as_str().into()));
 	params.push(("year".into(), quarter.year_month.year.into()));
 	params.push(("month".into(), quarter.year_month.month.into()));
 
@@ -129,8 +195,19 @@ async fn update(q: &mut QueryObject<'_>, quarter: &mut FinancialInfo, data: FiQu
 	quarter.sales = data.sales;
 	quarter.profit = data.profit;
 	quarter.net_income = data.net_income;
+	quarter.operating_margin = data.operating_margin;
+	quarter.net_margin = data.net_margin;
+	quarter.roe = data.roe;
+	quarter.debt_ratio = data.debt_ratio;
+	quarter.quick_ratio = data.quick_ratio;
+	quarter.reserve_ratio = data.reserve_ratio;
+	quarter.eps = data.eps;
+	quarter.per = data.per;
+	quarter.bps = data.bps;
+	quarter.pbr = data.pbr;
 	quarter.dividend = data.dividend;
 	quarter.dividend_yield = data.dividend_yield;
+	quarter.derived = data.derived;
 	Ok(())
 }
 
@@ -140,7 +217,18 @@ impl<'a> From<&'a FiQuarterData> for SqlValues<'a> {
 			.with("sales", data.sales)
 			.with("profit", data.profit)
 			.with("net_income", data.net_income)
+			.with("operating_margin", data.operating_margin)
+			.with("net_margin", data.net_margin)
+			.with("roe", data.roe)
+			.with("debt_ratio", data.debt_ratio)
+			.with("quick_ratio", data.quick_ratio)
+			.with("reserve_ratio", data.reserve_ratio)
+			.with("eps", data.eps)
+			.with("per", data.per)
+			.with("bps", data.bps)
+			.with("pbr", data.pbr)
 			.with("dividend", data.dividend)
 			.with("dividend_yield", data.dividend_yield)
+			.with("derived", data.derived)
 	}
 }
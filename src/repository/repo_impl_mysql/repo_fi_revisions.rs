@@ -0,0 +1,124 @@
+use async_trait::async_trait;
+use chrono::{NaiveDate, NaiveDateTime};
+use mysql_async::{params, prelude::FromRow};
+use repo_helper::{SqlValues, database_table, mysql::{MySqlHelper, QueryObject}};
+use rust_decimal::Decimal;
+
+use crate::{entities::{FiRevision, FiRevisionsDao}, types::{Error, FiTerm, YearMonth}};
+use crate::repository::repo_impl_mysql::{convert::IntoRepoResult, repo::RepoImpl, repo_tx::RepoTxImpl};
+
+
+#[async_trait]
+impl FiRevisionsDao for RepoImpl {
+	async fn list(&self, stock_code: &str, term: FiTerm) -> Result<Vec<FiRevision>, Error> {
+		let mut q = self.get_query_object().await?;
+		list(&mut q, stock_code, term).await
+	}
+	async fn insert(&self, revision: &FiRevision) -> Result<(), Error> {
+		let mut q = self.get_query_object().await?;
+		insert(&mut q, revision).await
+	}
+}
+
+#[async_trait]
+impl FiRevisionsDao for RepoTxImpl {
+	async fn list(&self, stock_code: &str, term: FiTerm) -> Result<Vec<FiRevision>, Error> {
+		let mut q = self.get_query_object().await?;
+		list(&mut q, stock_code, term).await
+	}
+	async fn insert(&self, revision: &FiRevision) -> Result<(), Error> {
+		let mut q = self.get_query_object().await?;
+		insert(&mut q, revision).await
+	}
+}
+
+
+database_table! {
+	#[table_name = "fi_revisions", derive(FromRow)]
+	EntityRow {
+		stock_code: String,
+		term: String,
+		basis: String,
+		year: u16,
+		month: u8,
+		field: String,
+		old_value: Option<Decimal>,
+		new_value: Option<Decimal>,
+		applied: bool,
+		source: String,
+		revised_at: NaiveDateTime,
+	}
+}
+impl TryFrom<EntityRow> for FiRevision {
+	type Error = Error;
+
+	fn try_from(value: EntityRow) -> Result<Self, Self::Error> {
+		Ok(Self {
+			stock_code: value.stock_code,
+			term: value.term.as_str().try_into()?,
+			basis: value.basis.as_str().try_into()?,
+			year_month: YearMonth::new(value.year, value.month),
+			field: value.field,
+			old_value: value.old_value,
+			new_value: value.new_value,
+			applied: value.applied,
+			source: value.source,
+			revised_at: value.revised_at,
+		})
+	}
+}
+
+
+const TABLE: &str = EntityRow::TABLE_NAME;
+const FIELDS: &str = EntityRow::TABLE_FIELDS;
+
+async fn list(q: &mut QueryObject<'_>, stock_code: &str, term: FiTerm) -> Result<Vec<FiRevision>, Error> {
+	let sql = format!("SELECT {FIELDS} FROM {TABLE} WHERE stock_code=:stock_code AND term=:term ORDER BY revised_at, id");
+	log::debug!("{sql} -- stock_code={stock_code}, term={}", term.as_str());
+
+	let stmt = q.prep(sql).await?;
+	let params = params! { stock_code, "term" => term.as_str() };
+	let rows: Vec<EntityRow> = q.exec(&stmt, params).await?;
+	rows.into_repo_result()
+}
+
+/// `date` 다음날부터 기록된 수정 내역
+pub(super) async fn list_after(q: &mut QueryObject<'_>, stock_code: &str, term: FiTerm, date: NaiveDate) -> Result<Vec<FiRevision>, Error> {
+	let sql = format!("SELECT {FIELDS} FROM {TABLE} WHERE stock_code=:stock_code AND term=:term AND revised_at>=:revised_after ORDER BY revised_at, id");
+	log::debug!("{sql} -- stock_code={stock_code}, term={}, date={date}", term.as_str());
+
+	let revised_after = date.succ_opt().unwrap_or(date).and_hms_opt(0, 0, 0).unwrap();
+	let stmt = q.prep(sql).await?;
+	let params = params! { stock_code, "term" => term.as_str(), revised_after };
+	let rows: Vec<EntityRow> = q.exec(&stmt, params).await?;
+	rows.into_repo_result()
+}
+
+async fn insert(q: &mut QueryObject<'_>, revision: &FiRevision) -> Result<(), Error> {
+	let values = SqlValues::from(revision);
+	let insert_clause = values.with_named_binding_holder();
+	let sql = format!("INSERT INTO {TABLE} SET {insert_clause}");
+	log::debug!("{} -- {}", sql, values);
+
+	let stmt = q.prep(sql).await?;
+	q.exec_drop(stmt, values.params()).await?;
+	Ok(())
+}
+
+
+impl<'a> From<&'a FiRevision> for SqlValues<'a> {
+	fn from(revision: &'a FiRevision) -> Self {
+		SqlValues::default()
+			.with("stock_code", &revision.stock_code)
+			.with("term", revision.term.as_str())
+			.with("basis", revision.basis.as_str())
+			.with("year", revision.year_month.year)
+			.with("month", revision.year_month.month)
+			.with("field", &revision.field)
+			.with("old_value", revision.old_value)
+			.with("new_value", revision.new_value)
+			.with("applied", revision.applied)
+			.with("source", &revision.source)
+			.with("revised_at", revision.revised_at)
+	}
+}
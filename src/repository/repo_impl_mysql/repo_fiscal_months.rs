@@ -0,0 +1,104 @@
+use async_trait::async_trait;
+use mysql_async::{params, prelude::FromRow};
+use repo_helper::{SqlValues, database_table, mysql::{MySqlHelper, QueryObject}};
+
+use crate::{entities::{FiscalMonth, FiscalMonthsDao}, types::{Error, YearMonth}};
+use crate::repository::repo_impl_mysql::{repo::RepoImpl, repo_tx::RepoTxImpl};
+
+
+#[async_trait]
+impl FiscalMonthsDao for RepoImpl {
+	async fn list(&self) -> Result<Vec<FiscalMonth>, Error> {
+		let mut q = self.get_query_object().await?;
+		list(&mut q).await
+	}
+	async fn find(&self, stock_code: &str) -> Result<Option<FiscalMonth>, Error> {
+		let mut q = self.get_query_object().await?;
+		find(&mut q, stock_code).await
+	}
+	async fn upsert(&self, fiscal_month: &FiscalMonth) -> Result<(), Error> {
+		let mut q = self.get_query_object().await?;
+		upsert(&mut q, fiscal_month).await
+	}
+}
+
+#[async_trait]
+impl FiscalMonthsDao for RepoTxImpl {
+	async fn list(&self) -> Result<Vec<FiscalMonth>, Error> {
+		let mut q = self.get_query_object().await?;
+		list(&mut q).await
+	}
+	async fn find(&self, stock_code: &str) -> Result<Option<FiscalMonth>, Error> {
+		let mut q = self.get_query_object().await?;
+		find(&mut q, stock_code).await
+	}
+	async fn upsert(&self, fiscal_month: &FiscalMonth) -> Result<(), Error> {
+		let mut q = self.get_query_object().await?;
+		upsert(&mut q, fiscal_month).await
+	}
+}
+
+
+database_table! {
+	#[table_name = "fi_fiscal_months", derive(FromRow)]
+	EntityRow {
+		stock_code: String,
+		fiscal_month: u8,
+		since_year: u16,
+		since_month: u8,
+	}
+}
+impl From<EntityRow> for FiscalMonth {
+	fn from(value: EntityRow) -> Self {
+		Self {
+			stock_code: value.stock_code,
+			month: value.fiscal_month,
+			since: YearMonth::new(value.since_year, value.since_month),
+		}
+	}
+}
+
+
+const TABLE: &str = EntityRow::TABLE_NAME;
+const FIELDS: &str = EntityRow::TABLE_FIELDS;
+
+async fn list(q: &mut QueryObject<'_>) -> Result<Vec<FiscalMonth>, Error> {
+	let sql = format!("SELECT {FIELDS} FROM {TABLE} ORDER BY stock_code");
+	log::debug!("{sql}");
+
+	let stmt = q.prep(sql).await?;
+	let rows: Vec<EntityRow> = q.exec(&stmt, ()).await?;
+	Ok(rows.into_iter().map(FiscalMonth::from).collect())
+}
+
+async fn find(q: &mut QueryObject<'_>, stock_code: &str) -> Result<Option<FiscalMonth>, Error> {
+	let sql = format!("SELECT {FIELDS} FROM {TABLE} WHERE stock_code=:stock_code");
+	log::debug!("{sql} -- stock_code={stock_code}");
+
+	let stmt = q.prep(sql).await?;
+	let params = params! { stock_code };
+	let ent: Option<EntityRow> = q.exec_first(&stmt, params).await?;
+	Ok(ent.map(FiscalMonth::from))
+}
+
+async fn upsert(q: &mut QueryObject<'_>, fiscal_month: &FiscalMonth) -> Result<(), Error> {
+	let values = SqlValues::from(fiscal_month);
+	let insert_clause = values.with_named_binding_holder();
+	let sql = format!("INSERT INTO {TABLE} SET {insert_clause} ON DUPLICATE KEY UPDATE {insert_clause}");
+	log::debug!("{} -- {}", sql, values);
+
+	let stmt = q.prep(sql).await?;
+	q.exec_drop(stmt, values.params()).await?;
+	Ok(())
+}
+
+
+impl<'a> From<&'a FiscalMonth> for SqlValues<'a> {
+	fn from(fiscal_month: &'a FiscalMonth) -> Self {
+		SqlValues::default()
+			.with("stock_code", &fiscal_month.stock_code)
+			.with("fiscal_month", fiscal_month.month)
+			.with("since_year", fiscal_month.since.year)
+			.with("since_month", fiscal_month.since.month)
+	}
+}
@@ -0,0 +1,94 @@
+use async_trait::async_trait;
+use chrono::NaiveDate;
+use mysql_async::prelude::FromRow;
+use repo_helper::{SqlValues, database_table, mysql::{MySqlHelper, QueryObject}};
+
+use crate::types::Error;
+use crate::entities::{ListingChange, ListingChangesDao};
+use crate::repository::repo_impl_mysql::{convert::IntoRepoResult, repo::RepoImpl, repo_tx::RepoTxImpl};
+
+
+#[async_trait]
+impl ListingChangesDao for RepoImpl {
+	async fn list(&self) -> Result<Vec<ListingChange>, Error> {
+		let mut q = self.get_query_object().await?;
+		list(&mut q).await
+	}
+	async fn insert(&self, change: &ListingChange) -> Result<(), Error> {
+		let mut q = self.get_query_object().await?;
+		insert(&mut q, change).await
+	}
+}
+
+#[async_trait]
+impl ListingChangesDao for RepoTxImpl {
+	async fn list(&self) -> Result<Vec<ListingChange>, Error> {
+		let mut q = self.get_query_object().await?;
+		list(&mut q).await
+	}
+	async fn insert(&self, change: &ListingChange) -> Result<(), Error> {
+		let mut q = self.get_query_object().await?;
+		insert(&mut q, change).await
+	}
+}
+
+
+database_table! {
+	#[table_name = "item_listing_changes", derive(FromRow)]
+	EntityRow {
+		code: String,
+		change_date: NaiveDate,
+		kind: String,
+		name: String,
+		market: String,
+	}
+}
+impl TryFrom<EntityRow> for ListingChange {
+	type Error = Error;
+
+	fn try_from(value: EntityRow) -> Result<Self, Self::Error> {
+		Ok(Self {
+			code: value.code,
+			change_date: value.change_date,
+			kind: value.kind.as_str().try_into()?,
+			name: value.name,
+			market: value.market.as_str().try_into()?,
+		})
+	}
+}
+
+
+const TABLE: &str = EntityRow::TABLE_NAME;
+const FIELDS: &str = EntityRow::TABLE_FIELDS;
+
+async fn list(q: &mut QueryObject<'_>) -> Result<Vec<ListingChange>, Error> {
+	let sql = format!("SELECT {FIELDS} FROM {TABLE} ORDER BY change_date, code");
+	log::debug!("{sql}");
+
+	let stmt = q.prep(sql).await?;
+	let rows: Vec<EntityRow> = q.exec(&stmt, ()).await?;
+	rows.into_repo_result()
+}
+
+async fn insert(q: &mut QueryObject<'_>, change: &ListingChange) -> Result<(), Error> {
+	let values = SqlValues::from(change);
+	let insert_clause = values.with_named_binding_holder();
+	let sql = format!("INSERT INTO {TABLE} SET {insert_clause}");
+	log::debug!("{} -- {}", sql, values);
+
+	let stmt = q.prep(sql).await?;
+	q.exec_drop(stmt, values.params()).await?;
+	Ok(())
+}
+
+
+impl<'a> From<&'a ListingChange> for SqlValues<'a> {
+	fn from(change: &'a ListingChange) -> Self {
+		SqlValues::default()
+			.with("code", &change.code)
+			.with("change_date", change.change_date)
+			.with("kind", change.kind.as_str())
+			.with("name", &change.name)
+			.with("market", change.market.as_str())
+	}
+}
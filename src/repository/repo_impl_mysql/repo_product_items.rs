@@ -0,0 +1,144 @@
+use async_trait::async_trait;
+use chrono::NaiveDate;
+use rust_decimal::Decimal;
+use mysql_async::prelude::FromRow;
+use repo_helper::{Filter, SqlFilter, SqlValues, database_table, mysql::{MySqlHelper, QueryObject}};
+
+use crate::types::{Error, Market};
+use crate::entities::{ProductItem, ProductItemsDao};
+use crate::repository::repo_impl_mysql::{convert::IntoRepoResult, repo::RepoImpl, repo_tx::RepoTxImpl};
+
+
+#[async_trait]
+impl ProductItemsDao for RepoImpl {
+	async fn list(&self, markets: &[Market]) -> Result<Vec<ProductItem>, Error> {
+		let mut q = self.get_query_object().await?;
+		list(&mut q, markets).await
+	}
+	async fn upsert(&self, item: &ProductItem) -> Result<(), Error> {
+		let mut q = self.get_query_object().await?;
+		upsert(&mut q, item).await
+	}
+}
+
+#[async_trait]
+impl ProductItemsDao for RepoTxImpl {
+	async fn list(&self, markets: &[Market]) -> Result<Vec<ProductItem>, Error> {
+		let mut q = self.get_query_object().await?;
+		list(&mut q, markets).await
+	}
+	async fn upsert(&self, item: &ProductItem) -> Result<(), Error> {
+		let mut q = self.get_query_object().await?;
+		upsert(&mut q, item).await
+	}
+}
+
+
+database_table! {
+	#[table_name = "item_info", derive(FromRow)]
+	EntityRow {
+		code: String,
+		info_date: NaiveDate,
+		name: String,
+		market: String,
+		std_code: Option<String>,
+		list_date: Option<NaiveDate>,
+		kind: Option<String>,
+		secu_group: Option<String>,
+		sect: Option<String>,
+		par: Option<u32>,
+		list_shares: Option<u64>,
+		etf_obj_idx: Option<String>,
+		etf_idx_inst: Option<String>,
+		etf_idx_multiplier: Option<i32>,
+		etf_replica_method: Option<String>,
+		etf_idx_market: Option<String>,
+		etf_idx_asset: Option<String>,
+		etf_op_company: Option<String>,
+		etf_fee_rate: Option<Decimal>,
+		etf_tax_type: Option<String>,
+	}
+}
+impl TryFrom<EntityRow> for ProductItem {
+	type Error = Error;
+
+	fn try_from(value: EntityRow) -> Result<Self, Self::Error> {
+		Ok(Self {
+			code: value.code,
+			info_date: value.info_date,
+			name: value.name,
+			market: value.market.as_str().try_into()?,
+			std_code: value.std_code,
+			list_date: value.list_date,
+			kind: value.kind,
+			secu_group: value.secu_group,
+			sect: value.sect,
+			par: value.par,
+			list_shares: value.list_shares,
+			etf_obj_idx: value.etf_obj_idx,
+			etf_idx_inst: value.etf_idx_inst,
+			etf_idx_multiplier: value.etf_idx_multiplier,
+			etf_replica_method: value.etf_replica_method,
+			etf_idx_market: value.etf_idx_market,
+			etf_idx_asset: value.etf_idx_asset,
+			etf_op_company: value.etf_op_company,
+			etf_fee_rate: value.etf_fee_rate,
+			etf_tax_type: value.etf_tax_type,
+		})
+	}
+}
+
+
+const TABLE: &str = EntityRow::TABLE_NAME;
+const FIELDS: &str = EntityRow::TABLE_FIELDS;
+
+async fn list(q: &mut QueryObject<'_>, markets: &[Market]) -> Result<Vec<ProductItem>, Error> {
+	let markets: Vec<&str> = markets.iter().map(|m| m.as_str()).collect();
+	let key = SqlFilter::default()
+		.with("market", &Filter::In(markets));
+	let key_clause = key.with_named_binding_holder();
+	let sql = format!("SELECT {FIELDS} FROM {TABLE} WHERE {key_clause} ORDER BY name");
+	log::debug!("{} -- {}", sql, key);
+
+	let stmt = q.prep(sql).await?;
+	let rows: Vec<EntityRow> = q.exec(&stmt, key.params()).await?;
+	rows.into_repo_result()
+}
+
+async fn upsert(q: &mut QueryObject<'_>, item: &ProductItem) -> Result<(), Error> {
+	let values = SqlValues::from(item);
+	let insert_clause = values.with_named_binding_holder();
+	let sql = format!("INSERT INTO {TABLE} SET {insert_clause} ON DUPLICATE KEY UPDATE {insert_clause}");
+	log::debug!("{} -- {}", sql, values);
+
+	let stmt = q.prep(sql).await?;
+	q.exec_drop(stmt, values.params()).await?;
+	Ok(())
+}
+
+
+impl<'a> From<&'a ProductItem> for SqlValues<'a> {
+	fn from(item: &'a ProductItem) -> Self {
+		SqlValues::default()
+			.with("code", &item.code)
+			.with("info_date", item.info_date)
+			.with("name", &item.name)
+			.with("market", item.market.as_str())
+			.with("std_code", item.std_code.as_deref())
+			.with("list_date", item.list_date)
+			.with("kind", item.kind.as_deref())
+			.with("secu_group", item.secu_group.as_deref())
+			.with("sect", item.sect.as_deref())
+			.with("par", item.par)
+			.with("list_shares", item.list_shares)
+			.with("etf_obj_idx", item.etf_obj_idx.as_deref())
+			.with("etf_idx_inst", item.etf_idx_inst.as_deref())
+			.with("etf_idx_multiplier", item.etf_idx_multiplier)
+			.with("etf_replica_method", item.etf_replica_method.as_deref())
+			.with("etf_idx_market", item.etf_idx_market.as_deref())
+			.with("etf_idx_asset", item.etf_idx_asset.as_deref())
+			.with("etf_op_company", item.etf_op_company.as_deref())
+			.with("etf_fee_rate", item.etf_fee_rate)
+			.with("etf_tax_type", item.etf_tax_type.as_deref())
+	}
+}
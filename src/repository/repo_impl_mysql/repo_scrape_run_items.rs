@@ -0,0 +1,95 @@
+use async_trait::async_trait;
+use chrono::NaiveDateTime;
+use mysql_async::{params, prelude::FromRow};
+use repo_helper::{SqlValues, database_table, mysql::{MySqlHelper, QueryObject}};
+
+use crate::types::Error;
+use crate::entities::{ScrapeRunItem, ScrapeRunItemsDao};
+use crate::repository::repo_impl_mysql::{convert::IntoRepoResult, repo::RepoImpl, repo_tx::RepoTxImpl};
+
+
+#[async_trait]
+impl ScrapeRunItemsDao for RepoImpl {
+	async fn list(&self, run_id: u64) -> Result<Vec<ScrapeRunItem>, Error> {
+		let mut q = self.get_query_object().await?;
+		list(&mut q, run_id).await
+	}
+	async fn upsert(&self, item: &ScrapeRunItem) -> Result<(), Error> {
+		let mut q = self.get_query_object().await?;
+		upsert(&mut q, item).await
+	}
+}
+
+#[async_trait]
+impl ScrapeRunItemsDao for RepoTxImpl {
+	async fn list(&self, run_id: u64) -> Result<Vec<ScrapeRunItem>, Error> {
+		let mut q = self.get_query_object().await?;
+		list(&mut q, run_id).await
+	}
+	async fn upsert(&self, item: &ScrapeRunItem) -> Result<(), Error> {
+		let mut q = self.get_query_object().await?;
+		upsert(&mut q, item).await
+	}
+}
+
+
+database_table! {
+	#[table_name = "scrape_run_items", derive(FromRow)]
+	EntityRow {
+		run_id: u64,
+		stock_code: String,
+		status: String,
+		message: Option<String>,
+		updated_at: NaiveDateTime,
+	}
+}
+impl TryFrom<EntityRow> for ScrapeRunItem {
+	type Error = Error;
+
+	fn try_from(value: EntityRow) -> Result<Self, Self::Error> {
+		Ok(Self {
+			run_id: value.run_id,
+			stock_code: value.stock_code,
+			status: value.status.as_str().try_into()?,
+			message: value.message,
+			updated_at: value.updated_at,
+		})
+	}
+}
+
+
+const TABLE: &str = EntityRow::TABLE_NAME;
+const FIELDS: &str = EntityRow::TABLE_FIELDS;
+
+async fn list(q: &mut QueryObject<'_>, run_id: u64) -> Result<Vec<ScrapeRunItem>, Error> {
+	let sql = format!("SELECT {FIELDS} FROM {TABLE} WHERE run_id=:run_id ORDER BY stock_code");
+	log::debug!("{sql} -- run_id={run_id}");
+
+	let stmt = q.prep(sql).await?;
+	let params = params! { run_id };
+	let rows: Vec<EntityRow> = q.exec(&stmt, params).await?;
+	rows.into_repo_result()
+}
+
+async fn upsert(q: &mut QueryObject<'_>, item: &ScrapeRunItem) -> Result<(), Error> {
+	let values = SqlValues::from(item);
+	let insert_clause = values.with_named_binding_holder();
+	let sql = format!("INSERT INTO {TABLE} SET {insert_clause} ON DUPLICATE KEY UPDATE {insert_clause}");
+	log::debug!("{} -- {}", sql, values);
+
+	let stmt = q.prep(sql).await?;
+	q.exec_drop(stmt, values.params()).await?;
+	Ok(())
+}
+
+
+impl<'a> From<&'a ScrapeRunItem> for SqlValues<'a> {
+	fn from(item: &'a ScrapeRunItem) -> Self {
+		SqlValues::default()
+			.with("run_id", item.run_id)
+			.with("stock_code", &item.stock_code)
+			.with("status", item.status.as_str())
+			.with("message", item.message.as_deref())
+			.with("updated_at", item.updated_at)
+	}
+}
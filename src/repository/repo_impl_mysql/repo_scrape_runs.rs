@@ -0,0 +1,106 @@
+use async_trait::async_trait;
+use chrono::NaiveDateTime;
+use mysql_async::{params, prelude::FromRow};
+use repo_helper::{database_table, mysql::QueryObject};
+
+use crate::types::Error;
+use crate::entities::{ScrapeRun, ScrapeRunsDao};
+use crate::repository::repo_impl_mysql::{repo::RepoImpl, repo_tx::RepoTxImpl};
+
+
+#[async_trait]
+impl ScrapeRunsDao for RepoImpl {
+	async fn last_unfinished(&self, source: &str) -> Result<Option<ScrapeRun>, Error> {
+		let mut q = self.get_query_object().await?;
+		last_unfinished(&mut q, source).await
+	}
+	async fn insert(&self, source: &str, started_at: NaiveDateTime) -> Result<ScrapeRun, Error> {
+		let mut q = self.get_query_object().await?;
+		insert(&mut q, source, started_at).await
+	}
+	async fn finish(&self, id: u64, finished_at: NaiveDateTime) -> Result<(), Error> {
+		let mut q = self.get_query_object().await?;
+		finish(&mut q, id, finished_at).await
+	}
+}
+
+#[async_trait]
+impl ScrapeRunsDao for RepoTxImpl {
+	async fn last_unfinished(&self, source: &str) -> Result<Option<ScrapeRun>, Error> {
+		let mut q = self.get_query_object().await?;
+		last_unfinished(&mut q, source).await
+	}
+	async fn insert(&self, source: &str, started_at: NaiveDateTime) -> Result<ScrapeRun, Error> {
+		let mut q = self.get_query_object().await?;
+		insert(&mut q, source, started_at).await
+	}
+	async fn finish(&self, id: u64, finished_at: NaiveDateTime) -> Result<(), Error> {
+		let mut q = self.get_query_object().await?;
+		finish(&mut q, id, finished_at).await
+	}
+}
+
+
+database_table! {
+	#[table_name = "scrape_runs", derive(FromRow)]
+	EntityRow {
+		id: u64,
+		source: String,
+		started_at: NaiveDateTime,
+		finished_at: Option<NaiveDateTime>,
+	}
+}
+impl From<EntityRow> for ScrapeRun {
+	fn from(value: EntityRow) -> Self {
+		Self {
+			id: value.id,
+			source: value.source,
+			started_at: value.started_at,
+			finished_at: value.finished_at,
+		}
+	}
+}
+
+
+const TABLE: &str = EntityRow::TABLE_NAME;
+const FIELDS: &str = EntityRow::TABLE_FIELDS;
+
+async fn last_unfinished(q: &mut QueryObject<'_>, source: &str) -> Result<Option<ScrapeRun>, Error> {
+	let sql = format!("SELECT {FIELDS} FROM {TABLE} WHERE source=:source AND finished_at IS NULL ORDER BY id DESC LIMIT 1");
+	log::debug!("{sql} -- source={source}");
+
+	let stmt = q.prep(sql).await?;
+	let params = params! { source };
+	let ent: Option<EntityRow> = q.exec_first(&stmt, params).await?;
+	Ok(ent.map(ScrapeRun::from))
+}
+
+async fn insert(q: &mut QueryObject<'_>, source: &str, started_at: NaiveDateTime) -> Result<ScrapeRun, Error> {
+	let sql = format!("INSERT INTO {TABLE} SET source=:source, started_at=:started_at");
+	log::debug!("{sql} -- source={source}, started_at={started_at}");
+
+	let stmt = q.prep(sql).await?;
+	q.exec_drop(stmt, params! { source, started_at }).await?;
+
+	// 같은 커넥션에서 조회해야 방금 생성된 ID 를 얻을 수 있다
+	let stmt = q.prep("SELECT LAST_INSERT_ID()".to_string()).await?;
+	let id: Option<u64> = q.exec_first(&stmt, ()).await?;
+	let Some(id) = id else {
+		return Err("Failed to get the id of the new scrape run".into());
+	};
+	Ok(ScrapeRun {
+		id,
+		source: source.to_string(),
+		started_at,
+		finished_at: None,
+	})
+}
+
+async fn finish(q: &mut QueryObject<'_>, id: u64, finished_at: NaiveDateTime) -> Result<(), Error> {
+	let sql = format!("UPDATE {TABLE} SET finished_at=:finished_at WHERE id=:id");
+	log::debug!("{sql} -- id={id}, finished_at={finished_at}");
+
+	let stmt = q.prep(sql).await?;
+	q.exec_drop(stmt, params! { id, finished_at }).await?;
+	Ok(())
+}
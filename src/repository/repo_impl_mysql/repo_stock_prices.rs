@@ -2,7 +2,7 @@ use std::ops::Range;
 
 use async_trait::async_trait;
 use chrono::NaiveDate;
-use mysql_async::{params, prelude::FromRow};
+use mysql_async::{Value, params, prelude::FromRow};
 use repo_helper::{database_table, mysql::QueryObject};
 
 use crate::{entities::{StockPrice, StockPriceRange, StockPricesDao}, types::Error};
@@ -15,10 +15,18 @@ impl StockPricesDao for RepoImpl {
 		let mut q = self.get_query_object().await?;
 		latest(&mut q, code).await
 	}
+	async fn latest_as_of(&self, code: &str, date: NaiveDate) -> Result<Option<StockPrice>, Error> {
+		let mut q = self.get_query_object().await?;
+		latest_as_of(&mut q, code, date).await
+	}
 	async fn range(&self, code: &str, range: Range<NaiveDate>) -> Result<Option<StockPriceRange>, Error> {
 		let mut q = self.get_query_object().await?;
 		query_range(&mut q, code, range).await
 	}
+	async fn upsert(&self, prices: &[StockPrice]) -> Result<(), Error> {
+		let mut q = self.get_query_object().await?;
+		upsert(&mut q, prices).await
+	}
 }
 
 #[async_trait]
@@ -27,10 +35,18 @@ impl StockPricesDao for RepoTxImpl {
 		let mut q = self.get_query_object().await?;
 		latest(&mut q, code).await
 	}
+	async fn latest_as_of(&self, code: &str, date: NaiveDate) -> Result<Option<StockPrice>, Error> {
+		let mut q = self.get_query_object().await?;
+		latest_as_of(&mut q, code, date).await
+	}
 	async fn range(&self, code: &str, range: Range<NaiveDate>) -> Result<Option<StockPriceRange>, Error> {
 		let mut q = self.get_query_object().await?;
 		query_range(&mut q, code, range).await
 	}
+	async fn upsert(&self, prices: &[StockPrice]) -> Result<(), Error> {
+		let mut q = self.get_query_object().await?;
+		upsert(&mut q, prices).await
+	}
 }
 
 
@@ -44,6 +60,8 @@ database_table! {
 		lowest: Option<u32>,
 		closing: Option<u32>,
 		diff: Option<i32>,
+		volume: Option<u64>,
+		trading_value: Option<u64>,
 	}
 }
 impl TryFrom<EntityRow> for StockPrice {
@@ -58,6 +76,8 @@ impl TryFrom<EntityRow> for StockPrice {
 			lowest: value.lowest,
 			closing: value.closing,
 			diff: value.diff,
+			volume: value.volume,
+			trading_value: value.trading_value,
 		})
 	}
 }
@@ -78,6 +98,17 @@ async fn latest(q: &mut QueryObject<'_>, stock_code: &str) -> Result<Option<Stoc
 	Ok(fi)
 }
 
+async fn latest_as_of(q: &mut QueryObject<'_>, stock_code: &str, date: NaiveDate) -> Result<Option<StockPrice>, Error> {
+	let sql = format!("SELECT {FIELDS} FROM {TABLE} WHERE code=:stock_code AND ord_date<=:date ORDER BY ord_date desc LIMIT 1");
+	log::debug!("{sql} -- stock_code={stock_code}, date={date}");
+
+	let stmt = q.prep(sql).await?;
+	let params = params! { stock_code, date };
+	let ent: Option<EntityRow> = q.exec_first(&stmt, params).await?;
+	let price = ent.map(StockPrice::try_from).transpose()?;
+	Ok(price)
+}
+
 
 #[derive(FromRow)]
 struct StockPriceRangeEntityRow {
@@ -108,3 +139,34 @@ async fn query_range(q: &mut QueryObject<'_>, stock_code: &str, range: Range<Nai
 	let price_range = ent.map(StockPriceRange::try_from).transpose()?;
 	Ok(price_range)
 }
+
+
+/// 한 번의 INSERT 문에 담을 최대 행 수
+const UPSERT_CHUNK_SIZE: usize = 500;
+
+async fn upsert(q: &mut QueryObject<'_>, prices: &[StockPrice]) -> Result<(), Error> {
+	for chunk in prices.chunks(UPSERT_CHUNK_SIZE) {
+		let placeholders = vec!["(?,?,?,?,?,?,?,?,?)"; chunk.len()].join(",");
+		let sql = format!("INSERT INTO {TABLE} ({FIELDS}) VALUES {placeholders} \
+			ON DUPLICATE KEY UPDATE opening=VALUES(opening), highest=VALUES(highest), lowest=VALUES(lowest), closing=VALUES(closing), \
+			diff=VALUES(diff), volume=VALUES(volume), trading_value=VALUES(trading_value)");
+		log::debug!("INSERT INTO {TABLE} ... ON DUPLICATE KEY UPDATE ... -- {} rows, code={}", chunk.len(), chunk[0].stock_code);
+
+		let mut params = Vec::<Value>::with_capacity(chunk.len() * 9);
+		for price in chunk {
+			params.push(price.stock_code.as_str().into());
+			params.push(price.ord_date.into());
+			params.push(price.opening.into());
+			params.push(price.highest.into());
+			params.push(price.lowest.into());
+			params.push(price.closing.into());
+			params.push(price.diff.into());
+			params.push(price.volume.into());
+			params.push(price.trading_value.into());
+		}
+
+		let stmt = q.prep(sql).await?;
+		q.exec_drop(stmt, params).await?;
+	}
+	Ok(())
+}
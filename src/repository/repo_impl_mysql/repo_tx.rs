@@ -4,7 +4,7 @@ use repo_helper::mysql::QueryObject;
 use tokio::sync::Mutex;
 
 use crate::types::Error;
-use crate::entities::{EntityDao, FiAnnualsDao, FiQuartersDao, RepoTx, StockPricesDao, StocksDao};
+use crate::entities::{BalanceSheetsDao, CashFlowsDao, EntityDao, FiAnnualsDao, FiEstimatesDao, FiQuartersDao, FiRevisionsDao, FiscalMonthsDao, ListingChangesDao, ProductItemsDao, RepoTx, ScrapeRunItemsDao, ScrapeRunsDao, StockPricesDao, StocksDao};
 
 pub struct RepoTxImpl {
 	native_tx: Mutex<Transaction<'static>>,
@@ -25,6 +25,12 @@ impl EntityDao for RepoTxImpl {
 	fn stocks(&self) -> &(dyn StocksDao + Sync) {
 		self
 	}
+	fn product_items(&self) -> &(dyn ProductItemsDao + Sync) {
+		self
+	}
+	fn listing_changes(&self) -> &(dyn ListingChangesDao + Sync) {
+		self
+	}
 	fn stock_prices(&self) -> &(dyn StockPricesDao + Sync) {
 		self
 	}
@@ -34,6 +40,27 @@ impl EntityDao for RepoTxImpl {
 	fn fi_quarters(&self) -> &(dyn FiQuartersDao + Sync) {
 		self
 	}
+	fn fi_estimates(&self) -> &(dyn FiEstimatesDao + Sync) {
+		self
+	}
+	fn scrape_runs(&self) -> &(dyn ScrapeRunsDao + Sync) {
+		self
+	}
+	fn scrape_run_items(&self) -> &(dyn ScrapeRunItemsDao + Sync) {
+		self
+	}
+	fn fi_revisions(&self) -> &(dyn FiRevisionsDao + Sync) {
+		self
+	}
+	fn fiscal_months(&self) -> &(dyn FiscalMonthsDao + Sync) {
+		self
+	}
+	fn balance_sheets(&self) -> &(dyn BalanceSheetsDao + Sync) {
+		self
+	}
+	fn cash_flows(&self) -> &(dyn CashFlowsDao + Sync) {
+		self
+	}
 }
 
 impl AsRef<dyn EntityDao> for RepoTxImpl {
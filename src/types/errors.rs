@@ -88,3 +88,19 @@ impl From<csv::Error> for Error {
 		}
 	}
 }
+
+impl From<serde_json::Error> for Error {
+	fn from(err: serde_json::Error) -> Self {
+		Error {
+			message: err.to_string(),
+		}
+	}
+}
+
+impl From<quick_xml::DeError> for Error {
+	fn from(err: quick_xml::DeError) -> Self {
+		Error {
+			message: err.to_string(),
+		}
+	}
+}
@@ -1,11 +1,94 @@
 use std::{fmt::Display, str::FromStr};
 
-use chrono::{Datelike, NaiveDate};
+use chrono::{Datelike, Duration, NaiveDate};
 
 mod errors;
 
 pub use errors::*;
-use serde::{Serialize, Serializer};
+use rust_decimal::Decimal;
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+/// 실적 수치. 단위(억원, 원, %, 배)는 항목마다 다르다
+pub type Amount = Decimal;
+
+/// 실적 수치의 단위
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub enum Unit {
+	/// 원
+	Won,
+	/// 백만원
+	MillionWon,
+	/// 억원
+	HundredMillionWon,
+	/// %
+	Percent,
+	/// 배
+	Multiple,
+}
+
+impl Unit {
+	pub fn as_str(&self) -> &'static str {
+		match self {
+			Unit::Won => "WON",
+			Unit::MillionWon => "MILLION_WON",
+			Unit::HundredMillionWon => "HUNDRED_MILLION_WON",
+			Unit::Percent => "PERCENT",
+			Unit::Multiple => "MULTIPLE",
+		}
+	}
+
+	pub fn label(&self) -> &'static str {
+		match self {
+			Unit::Won => "원",
+			Unit::MillionWon => "백만원",
+			Unit::HundredMillionWon => "억원",
+			Unit::Percent => "%",
+			Unit::Multiple => "배",
+		}
+	}
+
+	/// 금액 단위이면 1 단위가 몇 원인지
+	pub fn won_scale(&self) -> Option<Amount> {
+		match self {
+			Unit::Won => Some(Amount::ONE),
+			Unit::MillionWon => Some(Amount::from(1_000_000)),
+			Unit::HundredMillionWon => Some(Amount::from(100_000_000)),
+			Unit::Percent | Unit::Multiple => None,
+		}
+	}
+
+	/// `value` 를 `to` 단위로 바꾼다. 같은 단위가 아니면 금액 단위끼리만 바꿀 수 있다
+	pub fn convert(&self, value: Amount, to: Unit) -> Option<Amount> {
+		if *self == to {
+			return Some(value);
+		}
+		let (from_scale, to_scale) = (self.won_scale()?, to.won_scale()?);
+		(value * from_scale).checked_div(to_scale)
+	}
+}
+
+impl FromStr for Unit {
+	type Err = Error;
+
+	fn from_str(s: &str) -> Result<Self, Self::Err> {
+		match s {
+			"WON" | "원" => Ok(Unit::Won),
+			"MILLION_WON" | "백만원" => Ok(Unit::MillionWon),
+			"HUNDRED_MILLION_WON" | "억원" => Ok(Unit::HundredMillionWon),
+			"PERCENT" | "%" => Ok(Unit::Percent),
+			"MULTIPLE" | "배" => Ok(Unit::Multiple),
+			_ => Err(format!("Unknown Unit Representation `{s}`").into()),
+		}
+	}
+}
+
+impl TryFrom<&str> for Unit {
+	type Error = Error;
+
+	fn try_from(value: &str) -> Result<Self, Self::Error> {
+		Unit::from_str(value)
+	}
+}
 
 #[derive(Debug, Copy, Clone, PartialEq, Eq)]
 pub enum Market {
@@ -45,6 +128,104 @@ impl TryFrom<&str> for Market {
 	}
 }
 
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub enum FiTerm {
+	Annual,
+	Quarter,
+}
+
+impl FiTerm {
+	pub fn as_str(&self) -> &'static str {
+		match self {
+			FiTerm::Annual => "ANNUAL",
+			FiTerm::Quarter => "QUARTER",
+		}
+	}
+
+	/// 정기보고서 제출기한. 사업보고서는 결산일로부터 90일, 분기/반기보고서는 45일 이내
+	pub fn filing_deadline(&self, period_end: YearMonth) -> NaiveDate {
+		let days = match self {
+			FiTerm::Annual => 90,
+			FiTerm::Quarter => 45,
+		};
+		period_end.last_day() + Duration::days(days)
+	}
+}
+
+impl FromStr for FiTerm {
+	type Err = Error;
+
+	fn from_str(s: &str) -> Result<Self, Self::Err> {
+		match s {
+			"ANNUAL" => Ok(FiTerm::Annual),
+			"QUARTER" => Ok(FiTerm::Quarter),
+			_ => Err(format!("Unknown FiTerm Representation `{s}`").into()),
+		}
+	}
+}
+
+impl TryFrom<&str> for FiTerm {
+	type Error = Error;
+
+	fn try_from(value: &str) -> Result<Self, Self::Error> {
+		FiTerm::from_str(value)
+	}
+}
+
+/// 재무제표 구분
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash, Default)]
+pub enum StatementBasis {
+	/// 연결
+	#[default]
+	Consolidated,
+	/// 별도
+	Separate,
+}
+
+impl StatementBasis {
+	pub fn as_str(&self) -> &'static str {
+		match self {
+			StatementBasis::Consolidated => "CONSOLIDATED",
+			StatementBasis::Separate => "SEPARATE",
+		}
+	}
+
+	pub fn label(&self) -> &'static str {
+		match self {
+			StatementBasis::Consolidated => "연결",
+			StatementBasis::Separate => "별도",
+		}
+	}
+
+	/// 다른 구분
+	pub fn other(&self) -> Self {
+		match self {
+			StatementBasis::Consolidated => StatementBasis::Separate,
+			StatementBasis::Separate => StatementBasis::Consolidated,
+		}
+	}
+}
+
+impl FromStr for StatementBasis {
+	type Err = Error;
+
+	fn from_str(s: &str) -> Result<Self, Self::Err> {
+		match s {
+			"CONSOLIDATED" | "연결" => Ok(StatementBasis::Consolidated),
+			"SEPARATE" | "별도" => Ok(StatementBasis::Separate),
+			_ => Err(format!("Unknown StatementBasis Representation `{s}`").into()),
+		}
+	}
+}
+
+impl TryFrom<&str> for StatementBasis {
+	type Error = Error;
+
+	fn try_from(value: &str) -> Result<Self, Self::Error> {
+		StatementBasis::from_str(value)
+	}
+}
+
 #[derive(Debug, Copy, Clone, PartialEq, Eq, Hash, Default)]
 pub struct YearMonth {
 	pub year: u16,
@@ -54,6 +235,55 @@ impl YearMonth {
 	pub fn new(year: u16, month: u8) -> Self {
 		Self { year, month }
 	}
+
+	/// 0년 1월부터 센 개월 수
+	fn month_index(&self) -> i32 {
+		self.year as i32 * 12 + self.month as i32 - 1
+	}
+
+	fn from_month_index(index: i32) -> Self {
+		Self::new(index.div_euclid(12) as u16, (index.rem_euclid(12) + 1) as u8)
+	}
+
+	/// `months` 개월 후의 년월. 음수이면 이전 년월
+	pub fn add_months(&self, months: i32) -> Self {
+		Self::from_month_index(self.month_index() + months)
+	}
+
+	/// `months` 개월 전의 년월
+	pub fn sub_months(&self, months: i32) -> Self {
+		self.add_months(-months)
+	}
+
+	/// `quarters` 분기 후의 년월
+	pub fn add_quarters(&self, quarters: i32) -> Self {
+		self.add_months(quarters * 3)
+	}
+
+	/// `quarters` 분기 전의 년월
+	pub fn sub_quarters(&self, quarters: i32) -> Self {
+		self.add_months(-quarters * 3)
+	}
+
+	/// `other` 부터 몇 개월 뒤인지. `other` 가 더 나중이면 음수
+	pub fn months_since(&self, other: YearMonth) -> i32 {
+		self.month_index() - other.month_index()
+	}
+
+	/// 이 년월부터 `end` 까지(포함) 한 달씩
+	pub fn months_through(&self, end: YearMonth) -> impl Iterator<Item = YearMonth> + use<> {
+		(self.month_index()..=end.month_index()).map(Self::from_month_index)
+	}
+
+	/// 해당 월의 첫날
+	pub fn first_day(&self) -> NaiveDate {
+		NaiveDate::from_ymd_opt(self.year as i32, self.month as u32, 1).unwrap()
+	}
+
+	/// 해당 월의 마지막 날
+	pub fn last_day(&self) -> NaiveDate {
+		self.add_months(1).first_day().pred_opt().unwrap()
+	}
 }
 impl From<NaiveDate> for YearMonth {
 	fn from(date: NaiveDate) -> Self {
@@ -103,3 +333,215 @@ impl Serialize for YearMonth {
 		serializer.serialize_str(&self.to_string())
 	}
 }
+impl<'de> Deserialize<'de> for YearMonth {
+	fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+	where
+		D: Deserializer<'de>,
+	{
+		let s = String::deserialize(deserializer)?;
+		YearMonth::from_str(&s).map_err(serde::de::Error::custom)
+	}
+}
+
+/// 문자열(`2024.03`, `2024-03`)과 DATE 컬럼을 읽을 수 있고, `2024.03` 형식의 문자열로 쓴다
+#[cfg(feature = "mysql")]
+impl TryFrom<mysql_async::Value> for YearMonth {
+	type Error = mysql_async::FromValueError;
+
+	fn try_from(value: mysql_async::Value) -> Result<Self, Self::Error> {
+		match value {
+			mysql_async::Value::Bytes(ref bytes) => std::str::from_utf8(bytes).ok()
+				.and_then(|s| YearMonth::from_str(s).ok())
+				.ok_or(mysql_async::FromValueError(value)),
+			mysql_async::Value::Date(year, month, ..) if (1..=12).contains(&month) => Ok(YearMonth::new(year, month)),
+			_ => Err(mysql_async::FromValueError(value)),
+		}
+	}
+}
+#[cfg(feature = "mysql")]
+impl mysql_async::prelude::FromValue for YearMonth {
+	type Intermediate = YearMonth;
+}
+#[cfg(feature = "mysql")]
+impl From<YearMonth> for mysql_async::Value {
+	fn from(ym: YearMonth) -> Self {
+		mysql_async::Value::Bytes(ym.to_string().into_bytes())
+	}
+}
+
+/// 결산월 기준의 회계연도, 분기 계산
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub struct FiscalCalendar {
+	/// 결산월 (1~12)
+	pub fiscal_month: u8,
+}
+impl FiscalCalendar {
+	pub const DECEMBER: FiscalCalendar = FiscalCalendar { fiscal_month: 12 };
+
+	pub fn new(fiscal_month: u8) -> Result<Self, Error> {
+		if !(1..=12).contains(&fiscal_month) {
+			return Err(format!("Invalid fiscal month `{fiscal_month}`").into());
+		}
+		Ok(Self { fiscal_month })
+	}
+
+	/// 해당 월이 속한 회계연도 (회계연도가 끝나는 해)
+	pub fn fiscal_year(&self, ym: YearMonth) -> u16 {
+		if ym.month <= self.fiscal_month {
+			ym.year
+		} else {
+			ym.year + 1
+		}
+	}
+
+	/// 회계연도 안에서 해당 월이 속한 분기 (1~4)
+	pub fn quarter_index(&self, ym: YearMonth) -> u8 {
+		(ym.month + 11 - self.fiscal_month) % 12 / 3 + 1
+	}
+
+	/// 회계연도의 결산 년월
+	pub fn fiscal_year_end(&self, fiscal_year: u16) -> YearMonth {
+		YearMonth::new(fiscal_year, self.fiscal_month)
+	}
+
+	/// 해당 월이 속한 분기
+	pub fn quarter_of(&self, ym: YearMonth) -> FiscalQuarter {
+		FiscalQuarter {
+			calendar: *self,
+			fiscal_year: self.fiscal_year(ym),
+			quarter: self.quarter_index(ym),
+		}
+	}
+}
+
+/// 회계연도의 분기
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub struct FiscalQuarter {
+	pub calendar: FiscalCalendar,
+	/// 회계연도 (회계연도가 끝나는 해)
+	pub fiscal_year: u16,
+	/// 분기 (1~4)
+	pub quarter: u8,
+}
+impl FiscalQuarter {
+	/// 분기의 마지막 월. 분기 실적의 기준월이다
+	pub fn end(&self) -> YearMonth {
+		self.calendar.fiscal_year_end(self.fiscal_year).sub_quarters(4 - self.quarter as i32)
+	}
+
+	/// 분기의 첫 월
+	pub fn start(&self) -> YearMonth {
+		self.end().sub_months(2)
+	}
+
+	/// 분기에 속한 세 달
+	pub fn months(&self) -> [YearMonth; 3] {
+		let end = self.end();
+		[end.sub_months(2), end.sub_months(1), end]
+	}
+
+	pub fn first_day(&self) -> NaiveDate {
+		self.start().first_day()
+	}
+
+	pub fn last_day(&self) -> NaiveDate {
+		self.end().last_day()
+	}
+
+	/// `quarters` 분기 후의 분기. 음수이면 이전 분기
+	pub fn add_quarters(&self, quarters: i32) -> Self {
+		self.calendar.quarter_of(self.end().add_quarters(quarters))
+	}
+
+	/// 직전 분기
+	pub fn prev(&self) -> Self {
+		self.add_quarters(-1)
+	}
+
+	/// 다음 분기
+	pub fn next(&self) -> Self {
+		self.add_quarters(1)
+	}
+
+	/// 전년 동기
+	pub fn year_ago(&self) -> Self {
+		self.add_quarters(-4)
+	}
+}
+impl Display for FiscalQuarter {
+	fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+		write!(f, "{}.{}Q", self.fiscal_year, self.quarter)
+	}
+}
+impl PartialOrd for FiscalQuarter {
+	fn partial_cmp(&self, other: &Self) -> Option<std::cmp::Ordering> {
+		Some(self.cmp(other))
+	}
+}
+/// 분기말 순. 분기말이 같으면 결산월 순으로 `Eq` 와 맞춘다
+impl Ord for FiscalQuarter {
+	fn cmp(&self, other: &Self) -> std::cmp::Ordering {
+		(self.end(), self.calendar.fiscal_month).cmp(&(other.end(), other.calendar.fiscal_month))
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	fn ym(s: &str) -> YearMonth {
+		s.parse().unwrap()
+	}
+
+	#[test]
+	fn add_and_sub_months_across_years() {
+		assert_eq!(ym("2023.11").add_months(3), ym("2024.02"));
+		assert_eq!(ym("2024.01").sub_months(1), ym("2023.12"));
+		assert_eq!(ym("2024.03").sub_months(15), ym("2022.12"));
+		assert_eq!(ym("2024.12").add_months(-24), ym("2022.12"));
+		assert_eq!(ym("2023.12").add_quarters(1), ym("2024.03"));
+		assert_eq!(ym("2024.03").sub_quarters(2), ym("2023.09"));
+		assert_eq!(ym("2024.02").months_since(ym("2023.11")), 3);
+		assert_eq!(ym("2023.11").months_through(ym("2024.02")).count(), 4);
+	}
+
+	#[test]
+	fn last_day_of_february() {
+		assert_eq!(ym("2024.02").last_day(), NaiveDate::from_ymd_opt(2024, 2, 29).unwrap());
+		assert_eq!(ym("2023.02").last_day(), NaiveDate::from_ymd_opt(2023, 2, 28).unwrap());
+		assert_eq!(ym("2100.02").last_day(), NaiveDate::from_ymd_opt(2100, 2, 28).unwrap());
+		assert_eq!(ym("2023.12").last_day(), NaiveDate::from_ymd_opt(2023, 12, 31).unwrap());
+	}
+
+	#[test]
+	fn quarter_of_march_fiscal_year() {
+		let march = FiscalCalendar::new(3).unwrap();
+		let q1 = march.quarter_of(ym("2023.05"));
+		assert_eq!((q1.fiscal_year, q1.quarter), (2024, 1));
+		assert_eq!(q1.start(), ym("2023.04"));
+		assert_eq!(q1.end(), ym("2023.06"));
+		let q4 = march.quarter_of(ym("2024.03"));
+		assert_eq!((q4.fiscal_year, q4.quarter), (2024, 4));
+		assert_eq!(q4.next(), march.quarter_of(ym("2024.06")));
+		assert_eq!(q4.next().fiscal_year, 2025);
+		assert_eq!(q4.year_ago().end(), ym("2023.03"));
+		assert_eq!(q4.to_string(), "2024.4Q");
+	}
+
+	#[test]
+	fn fiscal_quarter_order_matches_eq() {
+		let december = FiscalCalendar::DECEMBER.quarter_of(ym("2024.06"));
+		let march = FiscalCalendar::new(3).unwrap().quarter_of(ym("2024.06"));
+		assert_eq!(december.end(), march.end());
+		assert_ne!(december, march);
+		assert_ne!(december.cmp(&march), std::cmp::Ordering::Equal);
+		assert!(december < FiscalCalendar::DECEMBER.quarter_of(ym("2024.09")));
+	}
+
+	#[test]
+	fn filing_deadline() {
+		assert_eq!(FiTerm::Annual.filing_deadline(ym("2023.12")), NaiveDate::from_ymd_opt(2024, 3, 30).unwrap());
+		assert_eq!(FiTerm::Quarter.filing_deadline(ym("2024.03")), NaiveDate::from_ymd_opt(2024, 5, 15).unwrap());
+		assert_eq!(FiTerm::Quarter.filing_deadline(ym("2024.06")), NaiveDate::from_ymd_opt(2024, 8, 14).unwrap());
+	}
+}